@@ -0,0 +1,190 @@
+use ic_kit::prelude::*;
+use ic_kit_certified::{AsHashTree, Map};
+
+/// The counter of each user, the root hash of this map is the certified data of the canister.
+pub struct Counters {
+    counters: Map<Principal, u64>,
+}
+
+impl Default for Counters {
+    fn default() -> Self {
+        Self {
+            counters: Map::new(),
+        }
+    }
+}
+
+/// The value of a counter along with what a client needs to verify it.
+#[derive(CandidType, Deserialize, Debug)]
+pub struct CertifiedCounter {
+    pub value: u64,
+    /// The data certificate of the canister.
+    pub certificate: Vec<u8>,
+    /// The CBOR encoded witness for the caller's counter.
+    pub witness: Vec<u8>,
+}
+
+/// Increment the counter of the caller by one and certify the new state.
+#[update]
+pub fn increment(counters: &mut Counters) -> u64 {
+    let caller = caller();
+    let value = counters.counters.get(&caller).copied().unwrap_or(0) + 1;
+    counters.counters.insert(caller, value);
+    ic::set_certified_data(&counters.counters.root_hash());
+    value
+}
+
+/// Certify the state of the canister again after an upgrade.
+#[post_upgrade]
+pub fn post_upgrade(counters: &Counters) {
+    ic::set_certified_data(&counters.counters.root_hash());
+}
+
+/// Return the counter of the caller, this only works as a non-replicated query since the
+/// data certificate is not available in update calls.
+#[query]
+pub fn get_counter(counters: &Counters) -> CertifiedCounter {
+    let caller = caller();
+    let value = counters.counters.get(&caller).copied().unwrap_or(0);
+    let certificate = ic::data_certificate().expect("No data certificate available.");
+    let witness = serde_cbor::to_vec(&counters.counters.witness(&caller)).unwrap();
+
+    CertifiedCounter {
+        value,
+        certificate,
+        witness,
+    }
+}
+
+#[derive(KitCanister)]
+#[candid_path("candid.did")]
+pub struct CertifiedCounterCanister;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ic_kit_certified::hashtree::{fork_hash, labeled_hash, leaf_hash};
+    use ic_kit_certified::{Hash, HashTree};
+    use serde_cbor::Value;
+
+    /// Compute the root hash of a CBOR encoded witness, the same way a client would.
+    fn reconstruct(tree: &Value) -> Hash {
+        let items = match tree {
+            Value::Array(items) => items.as_slice(),
+            _ => panic!("Invalid witness."),
+        };
+
+        match items {
+            [Value::Integer(0)] => HashTree::Empty.reconstruct(),
+            [Value::Integer(1), l, r] => fork_hash(&reconstruct(l), &reconstruct(r)),
+            [Value::Integer(2), Value::Bytes(label), t] => labeled_hash(label, &reconstruct(t)),
+            [Value::Integer(3), Value::Bytes(data)] => leaf_hash(data),
+            [Value::Integer(4), Value::Bytes(hash)] => hash.as_slice().try_into().unwrap(),
+            _ => panic!("Invalid witness."),
+        }
+    }
+
+    /// Return the leaf value under the given label in a CBOR encoded witness.
+    fn lookup<'a>(tree: &'a Value, label: &[u8]) -> Option<&'a [u8]> {
+        let items = match tree {
+            Value::Array(items) => items.as_slice(),
+            _ => return None,
+        };
+
+        match items {
+            [Value::Integer(1), l, r] => lookup(l, label).or_else(|| lookup(r, label)),
+            [Value::Integer(2), Value::Bytes(l), t] if l.as_slice() == label => match t {
+                Value::Array(leaf) => match leaf.as_slice() {
+                    [Value::Integer(3), Value::Bytes(data)] => Some(data.as_slice()),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    #[kit_test]
+    async fn test_certified_counter(replica: Replica) {
+        let c = replica.add_canister(CertifiedCounterCanister::anonymous());
+
+        for expected in 1..=3u64 {
+            let r = c
+                .new_call("increment")
+                .with_caller(*users::ALICE)
+                .perform()
+                .await
+                .decode_one::<u64>()
+                .unwrap();
+
+            assert_eq!(r, expected);
+        }
+
+        c.new_call("increment")
+            .with_caller(*users::BOB)
+            .perform()
+            .await
+            .assert_ok();
+
+        let r = c
+            .run_env(rt::types::Env::query("get_counter").with_sender(*users::ALICE))
+            .await
+            .decode_one::<CertifiedCounter>()
+            .unwrap();
+
+        assert_eq!(r.value, 3);
+
+        // The runtime does not sign the certificate, so it only contains the certified data.
+        let witness = serde_cbor::from_slice::<Value>(&r.witness).unwrap();
+        assert_eq!(&reconstruct(&witness)[..], r.certificate.as_slice());
+        assert_eq!(
+            lookup(&witness, users::ALICE.as_slice()),
+            Some(&3u64.to_be_bytes()[..])
+        );
+        assert_eq!(lookup(&witness, users::BOB.as_slice()), None);
+    }
+
+    #[kit_test]
+    async fn test_no_certificate_in_update(replica: Replica) {
+        let c = replica.add_canister(CertifiedCounterCanister::anonymous());
+
+        c.new_call("increment").perform().await.assert_ok();
+        c.new_call("get_counter").perform().await.assert_error();
+    }
+
+    #[kit_test]
+    async fn test_certify_in_post_upgrade(replica: Replica) {
+        let c = replica.add_canister(CertifiedCounterCanister::anonymous());
+
+        c.new_call("increment")
+            .with_caller(*users::ALICE)
+            .perform()
+            .await
+            .assert_ok();
+
+        // Overwrite the certified data, so only the post_upgrade hook can restore it.
+        c.custom(
+            || {
+                ic::set_certified_data(&[0; 32]);
+                ic_kit::utils::reply(&[]);
+            },
+            rt::types::Env::default(),
+        )
+        .await
+        .assert_ok();
+
+        // Lifecycle hooks do not reply, a trap would leave the certified data untouched.
+        c.post_upgrade().await;
+
+        let r = c
+            .run_env(rt::types::Env::query("get_counter").with_sender(*users::ALICE))
+            .await
+            .decode_one::<CertifiedCounter>()
+            .unwrap();
+
+        assert_eq!(r.value, 1);
+
+        let witness = serde_cbor::from_slice::<Value>(&r.witness).unwrap();
+        assert_eq!(&reconstruct(&witness)[..], r.certificate.as_slice());
+    }
+}
@@ -98,4 +98,316 @@ mod tests {
             2
         );
     }
+
+    #[kit_test]
+    async fn test_missing_method_or_reject(replica: Replica) {
+        let c = replica.add_canister(
+            CounterCanister::anonymous()
+                .or_reject(ic::RejectionCode::CanisterReject, "No such method."),
+        );
+
+        let r = c.new_call("decrement").perform().await;
+
+        assert!(matches!(
+            r.rejection_code(),
+            ic::RejectionCode::CanisterReject
+        ));
+        assert_eq!(r.rejection_message(), Some("No such method."));
+    }
+
+    #[test]
+    fn test_exported_methods() {
+        let c = CounterCanister::anonymous();
+
+        assert_eq!(
+            c.exported_methods(),
+            vec![
+                "canister_query get_counter",
+                "canister_update increment",
+                "canister_update increment_by",
+            ]
+        );
+    }
+
+    #[kit_test]
+    async fn test_max_payload_bytes(replica: Replica) {
+        let c = replica.add_canister(CounterCanister::anonymous().with_max_payload_bytes(16));
+
+        c.custom(
+            || {
+                let small = CallBuilder::new(Principal::anonymous(), "increment_by")
+                    .with_arg_raw(vec![0; 16])
+                    .perform_one_way();
+                assert!(small.is_ok());
+
+                let large = CallBuilder::new(Principal::anonymous(), "increment_by")
+                    .with_arg_raw(vec![0; 17])
+                    .perform_one_way();
+                assert!(large.is_err());
+
+                ic_kit::utils::reply(&[]);
+            },
+            rt::types::Env::default(),
+        )
+        .await
+        .assert_ok();
+    }
+
+    async fn canister_version(c: &rt::handle::CanisterHandle<'_>) -> u64 {
+        c.custom(
+            || {
+                let version = ic::canister_version();
+                ic_kit::utils::reply(&ic_kit::candid::encode_one(version).unwrap());
+            },
+            rt::types::Env::default(),
+        )
+        .await
+        .decode_one::<u64>()
+        .unwrap()
+    }
+
+    #[kit_test]
+    async fn test_canister_version(replica: Replica) {
+        let c = replica.add_canister(CounterCanister::anonymous());
+
+        // The version does not change unless the canister opts in.
+        assert_eq!(canister_version(&c).await, 0);
+        c.new_call("increment").perform().await.assert_ok();
+        assert_eq!(canister_version(&c).await, 0);
+    }
+
+    #[kit_test]
+    async fn test_auto_increment_version(replica: Replica) {
+        let c = replica.add_canister(
+            CounterCanister::anonymous()
+                .with_version(5)
+                .with_auto_increment_version(true),
+        );
+
+        assert_eq!(canister_version(&c).await, 5);
+
+        c.new_call("increment").perform().await.assert_ok();
+        c.new_call("increment").perform().await.assert_ok();
+        assert_eq!(canister_version(&c).await, 7);
+
+        // Queries do not change the version.
+        c.run_env(rt::types::Env::query("get_counter"))
+            .await
+            .assert_ok();
+        assert_eq!(canister_version(&c).await, 7);
+    }
+
+    #[kit_test]
+    async fn test_performance_counter(replica: Replica) {
+        let c = replica.add_canister(CounterCanister::anonymous());
+
+        c.custom(
+            || {
+                assert_eq!(ic_kit::utils::performance_counter(0), 1_000);
+                assert_eq!(ic_kit::utils::performance_counter(1), 1_000);
+                ic_kit::utils::reply(&[]);
+            },
+            rt::types::Env::default().with_performance_counter(1_000),
+        )
+        .await
+        .assert_ok();
+
+        c.custom(
+            || {
+                ic_kit::utils::performance_counter(2);
+            },
+            rt::types::Env::default(),
+        )
+        .await
+        .assert_error();
+    }
+
+    #[kit_test]
+    async fn test_stable_memory(replica: Replica) {
+        let c = replica.add_canister(CounterCanister::anonymous());
+
+        c.custom(
+            || {
+                assert_eq!(ic::stable_grow(1), Ok(0));
+                assert_eq!(ic::stable_size(), 1);
+
+                ic::stable_write(100, b"Hello");
+                let mut buf = [0u8; 5];
+                ic::stable_read(100, &mut buf);
+                assert_eq!(&buf, b"Hello");
+
+                ic_kit::utils::reply(&[]);
+            },
+            rt::types::Env::default(),
+        )
+        .await
+        .assert_ok();
+
+        // Reading past the end of the stable memory traps.
+        c.custom(
+            || {
+                let mut buf = [0u8; 5];
+                ic::stable_read((1 << 16) - 2, &mut buf);
+            },
+            rt::types::Env::default(),
+        )
+        .await
+        .assert_error();
+    }
+
+    #[kit_test]
+    async fn test_cycles_burn(replica: Replica) {
+        let c = replica.add_canister(CounterCanister::anonymous());
+
+        c.custom(
+            || {
+                assert_eq!(ic::cycles_burn(400), 400);
+                assert_eq!(ic::balance(), 600);
+                assert_eq!(ic::cycles_burn(1_000), 600);
+                assert_eq!(ic::balance(), 0);
+                ic_kit::utils::reply(&[]);
+            },
+            rt::types::Env::default().with_balance(1_000),
+        )
+        .await
+        .assert_ok();
+    }
+
+    #[kit_test]
+    async fn test_msg_cycles_accept_above_i64_max(replica: Replica) {
+        let c = replica.add_canister(CounterCanister::anonymous());
+
+        c.custom(
+            || {
+                let amount = (1 << 63) + 5;
+                assert_eq!(ic::msg_cycles_accept(amount), amount);
+                assert_eq!(ic::msg_cycles_available(), 10);
+                ic_kit::utils::reply(&[]);
+            },
+            rt::types::Env::default().with_cycles_available((1 << 63) + 15),
+        )
+        .await
+        .assert_ok();
+    }
+
+    #[cfg(feature = "experimental-cycles128")]
+    #[kit_test]
+    async fn test_msg_cycles_accept128(replica: Replica) {
+        let c = replica.add_canister(CounterCanister::anonymous());
+
+        c.custom(
+            || {
+                let amount = (3 << 64) + 5;
+                assert_eq!(ic::msg_cycles_accept(amount), amount);
+                assert_eq!(ic::msg_cycles_available(), 7);
+                ic_kit::utils::reply(&[]);
+            },
+            rt::types::Env::default().with_cycles_available((3 << 64) + 12),
+        )
+        .await
+        .assert_ok();
+    }
+
+    #[cfg(feature = "experimental-cycles128")]
+    #[kit_test]
+    async fn test_call_cycles_add128(replica: Replica) {
+        let c = replica.add_canister(CounterCanister::anonymous());
+
+        c.custom(
+            || {
+                // A call without payment only takes the cycles reserved for the response.
+                let before = ic::balance();
+                CallBuilder::new(ic::id(), "increment")
+                    .perform_one_way()
+                    .unwrap();
+                let reserved = before - ic::balance();
+
+                let amount = (3 << 64) + 5;
+                let before = ic::balance();
+                CallBuilder::new(ic::id(), "increment")
+                    .with_payment(amount)
+                    .perform_one_way()
+                    .unwrap();
+                assert_eq!(before - ic::balance(), amount + reserved);
+                ic_kit::utils::reply(&[]);
+            },
+            rt::types::Env::default().with_balance(1 << 100),
+        )
+        .await
+        .assert_ok();
+    }
+
+    #[kit_test]
+    async fn test_data_certificate(replica: Replica) {
+        let c = replica.add_canister(CounterCanister::anonymous());
+
+        c.custom(
+            || {
+                ic::set_certified_data(&[7; 32]);
+                ic_kit::utils::reply(&[]);
+            },
+            rt::types::Env::default(),
+        )
+        .await
+        .assert_ok();
+
+        c.custom(
+            || {
+                assert_eq!(ic::data_certificate(), None);
+                ic_kit::utils::reply(&[]);
+            },
+            rt::types::Env::update("increment"),
+        )
+        .await
+        .assert_ok();
+
+        c.custom(
+            || {
+                assert_eq!(ic::data_certificate(), Some(vec![7; 32]));
+                ic_kit::utils::reply(&[]);
+            },
+            rt::types::Env::query("get_counter"),
+        )
+        .await
+        .assert_ok();
+    }
+
+    /// A replacement for `increment` which increments the counter by two.
+    struct IncrementTwice;
+
+    impl rt::CanisterMethod for IncrementTwice {
+        const EXPORT_NAME: &'static str = "canister_update increment";
+
+        fn exported_method() {
+            let n = ic::with_mut(|counter: &mut Counter| counter.increment_by(2));
+            ic_kit::utils::reply(&ic_kit::candid::encode_one(n).unwrap());
+        }
+    }
+
+    #[kit_test]
+    async fn test_override_and_remove_method(replica: Replica) {
+        let mut canister = CounterCanister::anonymous().override_method::<IncrementTwice>();
+        assert_eq!(canister.remove_method("canister_update increment_by"), true);
+        assert_eq!(
+            canister.remove_method("canister_update increment_by"),
+            false
+        );
+
+        let c = replica.add_canister(canister);
+
+        let r = c
+            .new_call("increment")
+            .perform()
+            .await
+            .decode_one::<u64>()
+            .unwrap();
+
+        assert_eq!(r, 2);
+
+        c.new_call("increment_by")
+            .with_arg(1u8)
+            .perform()
+            .await
+            .assert_error();
+    }
 }
@@ -1,5 +1,6 @@
 use ic_kit::prelude::*;
 use ic_kit_example_counter::CounterCanister;
+use ic_kit_management::{CanisterIdRecord, CreateCanisterArgument};
 
 #[cfg(target_family = "wasm")]
 fn deploy<C: KitCanister>(_id: Principal) {
@@ -26,7 +27,13 @@ fn deploy<C: KitCanister>(id: Principal) {
 async fn deploy_counter() -> Principal {
     println!("Deploy counter!");
 
-    let id = Principal::from_text("whq4n-xiaaa-aaaam-qaazq-cai").unwrap();
+    let id = CallBuilder::new(Principal::management_canister(), "create_canister")
+        .with_arg(CreateCanisterArgument { settings: None })
+        .perform_one::<CanisterIdRecord>()
+        .await
+        .expect("ic-kit: could not create dynamic canister.")
+        .canister_id;
+
     deploy::<CounterCanister>(id);
 
     id
@@ -70,4 +77,122 @@ mod tests {
             1
         );
     }
+
+    #[kit_test]
+    async fn deploy_twice(replica: Replica) {
+        let factory = replica.add_canister(FactoryCounterCanister::anonymous());
+
+        let first = factory
+            .new_call("deploy_counter")
+            .perform()
+            .await
+            .decode_one::<Principal>()
+            .unwrap();
+
+        let second = factory
+            .new_call("deploy_counter")
+            .perform()
+            .await
+            .decode_one::<Principal>()
+            .unwrap();
+
+        assert_ne!(first, second);
+
+        replica
+            .get_canister(first)
+            .new_call("increment")
+            .perform()
+            .await
+            .assert_ok();
+
+        assert_eq!(
+            replica
+                .get_canister(second)
+                .new_call("get_counter")
+                .perform()
+                .await
+                .decode_one::<u64>()
+                .unwrap(),
+            0
+        );
+    }
+
+    async fn create_canister(replica: &Replica) -> Principal {
+        replica
+            .new_call(Principal::management_canister(), "create_canister")
+            .perform()
+            .await
+            .decode_one::<CanisterIdRecord>()
+            .unwrap()
+            .canister_id
+    }
+
+    #[kit_test]
+    async fn create_canister_skips_used_ids(replica: Replica) {
+        // Install a canister on the id that would be assigned to the second created canister.
+        let installed = Principal::from_slice(&[0, 0, 0, 0, 0, 0, 0, 1, 1, 1]);
+        replica.add_canister(CounterCanister::build(installed));
+
+        let first = create_canister(&replica).await;
+        let second = create_canister(&replica).await;
+        let third = create_canister(&replica).await;
+
+        assert_ne!(first, installed);
+        assert_ne!(second, installed);
+        assert_ne!(third, installed);
+        assert_ne!(first, second);
+        assert_ne!(second, third);
+        assert_ne!(first, third);
+    }
+
+    #[kit_test]
+    async fn create_canister_refunds_cycles(replica: Replica) {
+        let r = replica
+            .new_call(Principal::management_canister(), "create_canister")
+            .with_payment(1_000)
+            .perform()
+            .await;
+
+        r.assert_ok();
+        assert_eq!(r.cycles_refunded(), 1_000);
+    }
+
+    async fn raw_rand(replica: &Replica) -> Vec<u8> {
+        replica
+            .new_call(Principal::management_canister(), "raw_rand")
+            .perform()
+            .await
+            .decode_one::<Vec<u8>>()
+            .unwrap()
+    }
+
+    #[kit_test]
+    async fn deterministic_raw_rand(replica: Replica) {
+        let first = raw_rand(&replica).await;
+        let second = raw_rand(&replica).await;
+
+        assert_eq!(first.len(), 32);
+        assert_eq!(second.len(), 32);
+        assert_ne!(first, second);
+
+        // A fresh replica should produce the same sequence.
+        let other = Replica::default();
+        assert_eq!(raw_rand(&other).await, first);
+        assert_eq!(raw_rand(&other).await, second);
+    }
+
+    #[kit_test]
+    async fn seeded_raw_rand(replica: Replica) {
+        let a = Replica::with_rng_seed(1);
+        let b = Replica::with_rng_seed(2);
+
+        let a_stream = vec![raw_rand(&a).await, raw_rand(&a).await];
+        let b_stream = vec![raw_rand(&b).await, raw_rand(&b).await];
+        assert_ne!(a_stream, b_stream);
+
+        // The default replica uses the seed zero.
+        let zero = Replica::with_rng_seed(0);
+        assert_eq!(raw_rand(&zero).await, raw_rand(&replica).await);
+        assert_ne!(raw_rand(&a).await, raw_rand(&replica).await);
+    }
 }
@@ -6,6 +6,7 @@ use ic_kit_example_counter::CounterCanister;
 #[derive(Default)]
 struct MultiCounter {
     canister_ids: HashSet<Principal>,
+    pending: Vec<Principal>,
 }
 
 #[update]
@@ -27,6 +28,20 @@ fn add_counter(counters: &mut MultiCounter, canister_id: Principal) {
     counters.canister_ids.insert(canister_id);
 }
 
+#[update]
+fn schedule_increment(counters: &mut MultiCounter, canister_id: Principal) {
+    counters.pending.push(canister_id);
+}
+
+#[heartbeat]
+fn heartbeat(counters: &mut MultiCounter) {
+    for canister_id in counters.pending.drain(..) {
+        CallBuilder::new(canister_id, "increment")
+            .perform_one_way()
+            .expect("Expected the one way call to succeed.");
+    }
+}
+
 #[derive(KitCanister)]
 #[candid_path("candid.did")]
 pub struct MultiCounterCanister;
@@ -88,4 +103,43 @@ mod tests {
 
         println!("{:#?}", x);
     }
+
+    #[kit_test]
+    async fn test_heartbeat_deferred_call(replica: Replica) {
+        let counter_id = Principal::from_text("whq4n-xiaaa-aaaam-qaazq-cai").unwrap();
+
+        let canister = replica.add_canister(MultiCounterCanister::anonymous());
+        let counter = replica.add_canister(CounterCanister::build(counter_id));
+
+        canister
+            .new_call("schedule_increment")
+            .with_arg(&counter_id)
+            .perform()
+            .await
+            .assert_ok();
+
+        let r = counter
+            .new_call("get_counter")
+            .perform()
+            .await
+            .decode_one::<u64>()
+            .unwrap();
+
+        assert_eq!(r, 0);
+
+        canister.heartbeat().await;
+
+        // heartbeat() does not wait for the calls made by the hook, but they are sent before the
+        // next message is processed, so run the (now empty) heartbeat once more.
+        canister.heartbeat().await;
+
+        let r = counter
+            .new_call("get_counter")
+            .perform()
+            .await
+            .decode_one::<u64>()
+            .unwrap();
+
+        assert_eq!(r, 1);
+    }
 }
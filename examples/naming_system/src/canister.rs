@@ -22,6 +22,12 @@ fn get_name(registry: &Registry, user: Principal) -> Option<&String> {
     registry.names.get(&user)
 }
 
+/// Reject the ingress messages from anonymous users to `register`.
+#[inspect_message]
+fn inspect_message() -> bool {
+    ic_kit::utils::method_name() != "register" || caller() != Principal::anonymous()
+}
+
 #[derive(KitCanister)]
 #[candid_path("candid.did")]
 pub struct NamingSystemCanister;
@@ -68,4 +74,45 @@ mod tests {
 
         assert_eq!(bob_name, Some("Bob".to_string()));
     }
+
+    #[kit_test]
+    async fn test_inspect_message(replica: Replica) {
+        let ns = replica.add_canister(NamingSystemCanister::anonymous());
+
+        ns.run_env(rt::types::Env::inspect_message("register").with_sender(*users::ALICE))
+            .await
+            .assert_ok();
+
+        ns.inspect_message("register").await.assert_error();
+        ns.inspect_message("get_name").await.assert_ok();
+    }
+
+    #[kit_test]
+    async fn test_many_users(replica: Replica) {
+        let ns = replica.add_canister(NamingSystemCanister::anonymous());
+
+        for i in 0..10 {
+            let user = users::user(i);
+            assert_eq!(Principal::from_text(user.to_text()).unwrap(), user);
+
+            ns.new_call("register")
+                .with_caller(user)
+                .with_arg(format!("User {}", i))
+                .perform()
+                .await
+                .assert_ok();
+        }
+
+        for i in 0..10 {
+            let name = ns
+                .new_call("get_name")
+                .with_arg(users::user(i))
+                .perform()
+                .await
+                .decode_one::<Option<String>>()
+                .unwrap();
+
+            assert_eq!(name, Some(format!("User {}", i)));
+        }
+    }
 }
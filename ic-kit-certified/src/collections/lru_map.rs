@@ -0,0 +1,285 @@
+use crate::collections::map::Map;
+use crate::label::Label;
+use crate::rbtree::iterator::RbTreeIterator;
+use crate::{AsHashTree, Hash, HashTree};
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::fmt::{self, Debug, Formatter};
+
+/// A certified map with a bounded number of entries, once the map is full inserting
+/// a new key evicts the least recently used entry.
+///
+/// Only the key-value pairs are certified, the recency of the entries is kept outside
+/// of the tree, so reading a value does not change the root hash of the map.
+///
+/// # Example
+///
+/// ```
+/// use ic_kit_certified::LruMap;
+///
+/// let mut map = LruMap::<String, u8>::new(2);
+///
+/// map.insert("A".into(), 0);
+/// map.insert("B".into(), 1);
+/// map.get("A");
+/// map.insert("C".into(), 2);
+///
+/// assert_eq!(map.peek("A"), Some(&0));
+/// assert_eq!(map.peek("B"), None);
+/// ```
+pub struct LruMap<K: 'static + Label, V: AsHashTree + 'static> {
+    inner: Map<K, V>,
+    capacity: usize,
+    /// The tick of the last access to each key.
+    last_access: BTreeMap<K, u64>,
+    /// Map each access tick to the key, the first entry is the least recently used key.
+    recency: BTreeMap<u64, K>,
+    tick: u64,
+}
+
+impl<K: 'static + Label + Clone, V: AsHashTree + 'static> LruMap<K, V> {
+    /// Create a new, empty map that can hold at most `capacity` entries.
+    ///
+    /// # Panics
+    ///
+    /// If the capacity is zero.
+    #[inline]
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "LruMap: capacity must be non-zero.");
+
+        Self {
+            inner: Map::new(),
+            capacity,
+            last_access: BTreeMap::new(),
+            recency: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    /// Returns the maximum number of entries the map can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns `true` if the map does not contain any values.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns the number of elements in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Insert a key-value pair into the map and mark the key as the most recently
+    /// used one. Returns the previous value associated with the key, if the key is
+    /// new and the map is full, the least recently used entry is evicted first.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if !self.last_access.contains_key(&key) && self.len() == self.capacity {
+            self.evict();
+        }
+
+        self.touch(key.clone());
+        self.inner.insert(key, value)
+    }
+
+    /// Return the value associated with the given key and mark the key as the most
+    /// recently used one.
+    pub fn get<Q: ?Sized>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Label,
+    {
+        let owned = self.last_access.get_key_value(key)?.0.clone();
+        self.touch(owned);
+        self.inner.get(key)
+    }
+
+    /// Return the value associated with the given key without updating its recency.
+    #[inline]
+    pub fn peek<Q: ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Label,
+    {
+        self.inner.get(key)
+    }
+
+    /// Returns `true` if the map contains a value for the given key, this does not
+    /// update the recency of the key.
+    #[inline]
+    pub fn contains_key<Q: ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Label,
+    {
+        self.last_access.contains_key(key)
+    }
+
+    /// Remove the value associated with the given key from the map, returns the
+    /// previous value associated with the key.
+    pub fn remove<Q: ?Sized>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Label,
+    {
+        let tick = self.last_access.remove(key)?;
+        self.recency.remove(&tick);
+        self.inner.remove(key)
+    }
+
+    /// Return an iterator over the key-values in the map.
+    #[inline]
+    pub fn iter(&self) -> RbTreeIterator<K, V> {
+        self.inner.iter()
+    }
+
+    /// Create a HashTree witness for the value associated with given key, this does
+    /// not update the recency of the key.
+    #[inline]
+    pub fn witness<Q: ?Sized>(&self, key: &Q) -> HashTree
+    where
+        K: Borrow<Q>,
+        Q: Label,
+    {
+        self.inner.witness(key)
+    }
+
+    /// Return the underlying certified [`Map`].
+    #[inline]
+    pub fn as_map(&self) -> &Map<K, V> {
+        &self.inner
+    }
+
+    /// Mark the given key as the most recently used one.
+    fn touch(&mut self, key: K) {
+        self.tick += 1;
+
+        if let Some(tick) = self.last_access.insert(key.clone(), self.tick) {
+            self.recency.remove(&tick);
+        }
+
+        self.recency.insert(self.tick, key);
+    }
+
+    /// Remove the least recently used entry.
+    fn evict(&mut self) {
+        let tick = match self.recency.keys().next() {
+            Some(tick) => *tick,
+            None => return,
+        };
+
+        let key = self.recency.remove(&tick).unwrap();
+        self.last_access.remove(&key);
+        self.inner.remove(&key);
+    }
+}
+
+impl<K: 'static + Label, V: AsHashTree + 'static> AsHashTree for LruMap<K, V> {
+    #[inline]
+    fn root_hash(&self) -> Hash {
+        self.inner.root_hash()
+    }
+
+    #[inline]
+    fn as_hash_tree(&self) -> HashTree<'_> {
+        self.inner.as_hash_tree()
+    }
+}
+
+impl<K: 'static + Label, V: AsHashTree + 'static> Debug for LruMap<K, V>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eviction() {
+        let mut map = LruMap::<String, u32>::new(3);
+        assert_eq!(map.insert("A".into(), 0), None);
+        assert_eq!(map.insert("B".into(), 1), None);
+        assert_eq!(map.insert("C".into(), 2), None);
+        assert_eq!(map.len(), 3);
+
+        // Touch A so B becomes the least recently used key.
+        assert_eq!(map.get("A"), Some(&0));
+        assert_eq!(map.insert("D".into(), 3), None);
+
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.peek("A"), Some(&0));
+        assert_eq!(map.peek("B"), None);
+        assert_eq!(map.peek("C"), Some(&2));
+        assert_eq!(map.peek("D"), Some(&3));
+
+        // Updating an existing key should not evict anything.
+        assert_eq!(map.insert("C".into(), 4), Some(2));
+        assert_eq!(map.len(), 3);
+
+        // peek does not change the recency, so A is evicted.
+        map.peek("A");
+        map.insert("E".into(), 5);
+        assert_eq!(map.contains_key("A"), false);
+        assert_eq!(map.contains_key("C"), true);
+
+        assert_eq!(map.remove("D"), Some(3));
+        assert_eq!(map.remove("D"), None);
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn hash_does_not_depend_on_recency() {
+        let mut a = LruMap::<String, u32>::new(10);
+        let mut b = LruMap::<String, u32>::new(10);
+
+        for i in 0..10u32 {
+            a.insert(hex::encode(&i.to_be_bytes()), i);
+            b.insert(hex::encode(&i.to_be_bytes()), i);
+        }
+
+        for i in 0..10u32 {
+            a.get(&hex::encode(&i.to_be_bytes()));
+        }
+
+        for i in (0..10u32).rev() {
+            b.get(&hex::encode(&i.to_be_bytes()));
+        }
+
+        assert_eq!(a.root_hash(), b.root_hash());
+        assert_eq!(a.root_hash(), a.as_map().root_hash());
+    }
+
+    #[test]
+    fn witness() {
+        let mut map = LruMap::<String, u32>::new(50);
+
+        for i in 0..100u32 {
+            map.insert(hex::encode(&i.to_be_bytes()), i);
+        }
+
+        let root_hash = map.root_hash();
+
+        for i in 50..100u32 {
+            let key = hex::encode(&i.to_be_bytes());
+            let witness = map.witness(&key);
+            assert_eq!(witness.reconstruct(), root_hash);
+            assert_eq!(witness.get_leaf_values(), vec![&i.to_be_bytes()[..]]);
+        }
+
+        for i in 0..50u32 {
+            let key = hex::encode(&i.to_be_bytes());
+            assert_eq!(map.peek(&key), None);
+            assert_eq!(map.witness(&key).reconstruct(), root_hash);
+        }
+    }
+}
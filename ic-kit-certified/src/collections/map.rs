@@ -59,7 +59,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> Map<K, V> {
     pub fn remove<Q: ?Sized>(&mut self, key: &Q) -> Option<V>
     where
         K: Borrow<Q>,
-        Q: Ord,
+        Q: Label,
     {
         self.inner.delete(key).map(|(_, v)| v)
     }
@@ -69,7 +69,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> Map<K, V> {
     pub fn remove_entry<Q: ?Sized>(&mut self, key: &Q) -> Option<(K, V)>
     where
         K: Borrow<Q>,
-        Q: Ord,
+        Q: Label,
     {
         self.inner.delete(key)
     }
@@ -84,21 +84,32 @@ impl<K: 'static + Label, V: AsHashTree + 'static> Map<K, V> {
     pub fn get_mut<Q: ?Sized>(&mut self, key: &Q) -> Option<&mut V>
     where
         K: Borrow<Q>,
-        Q: Ord,
+        Q: Label,
     {
         self.inner.modify(key, |v| v)
     }
 
-    /// Return the value associated with the given key.
+    /// Return the value associated with the given key, this is a read-only lookup and
+    /// never recomputes any of the hashes in the tree.
     #[inline]
     pub fn get<Q: ?Sized>(&self, key: &Q) -> Option<&V>
     where
         K: Borrow<Q>,
-        Q: Ord,
+        Q: Label,
     {
         self.inner.get(key)
     }
 
+    /// Returns `true` if the map contains a value for the given key.
+    #[inline]
+    pub fn contains_key<Q: ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Label,
+    {
+        self.inner.get(key).is_some()
+    }
+
     /// Return an iterator over the key-values in the map.
     #[inline]
     pub fn iter(&self) -> RbTreeIterator<K, V> {
@@ -110,7 +121,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> Map<K, V> {
     pub fn witness<Q: ?Sized>(&self, key: &Q) -> HashTree
     where
         K: Borrow<Q>,
-        Q: Ord,
+        Q: Label,
     {
         self.inner.witness(key)
     }
@@ -128,8 +139,8 @@ impl<K: 'static + Label, V: AsHashTree + 'static> Map<K, V> {
     pub fn witness_value_range<Q1: ?Sized, Q2: ?Sized>(&self, first: &K, last: &K) -> HashTree<'_>
     where
         K: Borrow<Q1> + Borrow<Q2>,
-        Q1: Ord,
-        Q2: Ord,
+        Q1: Label,
+        Q2: Label,
     {
         self.inner.value_range(first, last)
     }
@@ -141,8 +152,8 @@ impl<K: 'static + Label, V: AsHashTree + 'static> Map<K, V> {
     pub fn witness_key_range<Q1: ?Sized, Q2: ?Sized>(&self, first: &K, last: &K) -> HashTree<'_>
     where
         K: Borrow<Q1> + Borrow<Q2>,
-        Q1: Ord,
-        Q2: Ord,
+        Q1: Label,
+        Q2: Label,
     {
         self.inner.key_range(first, last)
     }
@@ -153,7 +164,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> Map<K, V> {
     pub fn witness_keys_with_prefix<P: ?Sized>(&self, prefix: &P) -> HashTree<'_>
     where
         K: Prefix<P>,
-        P: Ord,
+        P: Label,
     {
         self.inner.keys_with_prefix(prefix)
     }
@@ -186,7 +197,7 @@ impl<K: 'static + Label, V: AsHashTree> Map<K, Seq<V>> {
     pub fn len_deep<Q: ?Sized>(&mut self, key: &Q) -> usize
     where
         K: Borrow<Q>,
-        Q: Ord,
+        Q: Label,
     {
         self.inner.get(key).map(|seq| seq.len()).unwrap_or(0)
     }
@@ -331,6 +342,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> AsHashTree for Map<K, V> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use candid::Principal;
 
     #[test]
     fn insert() {
@@ -351,6 +363,24 @@ mod tests {
         assert_eq!(map.get("D"), None);
     }
 
+    #[test]
+    fn contains_key() {
+        let mut map = Map::<String, u32>::new();
+        assert_eq!(map.contains_key("A"), false);
+
+        map.insert("A".into(), 0);
+        map.insert("B".into(), 1);
+        let root_hash = map.root_hash();
+
+        assert_eq!(map.contains_key("A"), true);
+        assert_eq!(map.contains_key("B"), true);
+        assert_eq!(map.contains_key("C"), false);
+        assert_eq!(map.root_hash(), root_hash);
+
+        map.remove("A");
+        assert_eq!(map.contains_key("A"), false);
+    }
+
     #[test]
     fn remove() {
         let mut map = Map::<String, u32>::new();
@@ -384,4 +414,89 @@ mod tests {
             assert_eq!(map.get(&hex::encode(&i.to_be_bytes())), None);
         }
     }
+
+    #[test]
+    fn principal_keys() {
+        let mut principals = (0..100u64)
+            .map(|i| Principal::from_slice(&i.to_be_bytes()))
+            .collect::<Vec<_>>();
+        principals.push(Principal::anonymous());
+        principals.push(Principal::management_canister());
+
+        let mut map = Map::<Principal, u64>::new();
+
+        for (i, principal) in principals.iter().enumerate() {
+            map.insert(principal.clone(), i as u64);
+        }
+
+        let root_hash = map.root_hash();
+        assert_eq!(map.as_hash_tree().reconstruct(), root_hash);
+
+        for (i, principal) in principals.iter().enumerate() {
+            let witness = map.witness(principal);
+            assert_eq!(witness.reconstruct(), root_hash);
+            assert_eq!(witness.get_labels(), vec![principal.as_slice()]);
+            assert_eq!(
+                witness.get_leaf_values(),
+                vec![&(i as u64).to_be_bytes()[..]]
+            );
+        }
+
+        // The keys should be sorted by their byte representation, the anonymous principal
+        // is shorter than the other ones but it comes after them.
+        let keys = map.iter().map(|(k, _)| k.as_slice()).collect::<Vec<_>>();
+        assert_eq!(keys.len(), principals.len());
+        assert!(keys.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(keys.last(), Some(&Principal::anonymous().as_slice()));
+
+        let witness = map.witness_keys();
+        let labels = witness.get_labels();
+        assert_eq!(labels, keys);
+
+        for principal in &principals {
+            assert_eq!(map.remove(principal).is_some(), true);
+            assert_eq!(map.as_hash_tree().reconstruct(), map.root_hash());
+        }
+
+        assert_eq!(map.is_empty(), true);
+    }
+
+    #[test]
+    fn signed_keys() {
+        let mut map = Map::<i32, u32>::new();
+
+        for i in -50..50 {
+            map.insert(i, 0);
+        }
+
+        // Negative numbers have the high bit set, so they come after the positive ones.
+        let labels = map.iter().map(|(k, _)| k.as_label()).collect::<Vec<_>>();
+        assert!(labels.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(map.iter().next().map(|(k, _)| *k), Some(0));
+        assert_eq!(map.witness_keys().reconstruct(), map.root_hash());
+        assert_eq!(map.witness(&-1).reconstruct(), map.root_hash());
+    }
+
+    #[test]
+    fn boxed_keys() {
+        let principals = vec![
+            Principal::anonymous(),
+            Principal::management_canister(),
+            Principal::from_slice(&[0, 1, 2, 3, 4, 5, 6, 7]),
+        ];
+
+        let mut map = Map::<Principal, u64>::new();
+        let mut boxed = Map::<Box<Principal>, u64>::new();
+
+        for principal in &principals {
+            map.insert(principal.clone(), 0);
+            boxed.insert(Box::new(principal.clone()), 0);
+        }
+
+        assert_eq!(boxed.root_hash(), map.root_hash());
+        assert_eq!(
+            boxed.iter().map(|(k, _)| k.as_slice()).collect::<Vec<_>>(),
+            map.iter().map(|(k, _)| k.as_slice()).collect::<Vec<_>>()
+        );
+    }
 }
@@ -0,0 +1,528 @@
+use crate::hashtree::{fork, fork_hash};
+use crate::{AsHashTree, Hash, HashTree};
+use candid::types::Type;
+use candid::CandidType;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::iter::FromIterator;
+use std::ops::Index;
+use std::slice::Iter;
+
+/// An append only log of `T` backed by a binary Merkle tree.
+///
+/// Unlike [`crate::Seq`] which is a simple hash chain, the log keeps the hash of
+/// every complete subtree so that both the inclusion proof of any past entry and
+/// the consistency proof between two versions of the log can be generated in
+/// logarithmic space.
+///
+/// The shape of the tree follows RFC 6962: a log of `n > 1` items is a fork whose
+/// left side is the complete tree over the first `k` items, where `k` is the
+/// largest power of two smaller than `n`, and whose right side is the tree over
+/// the remaining items.
+///
+/// # Example
+///
+/// ```
+/// use ic_kit_certified::{AsHashTree, MerkleLog};
+///
+/// let mut log = MerkleLog::<u8>::new();
+///
+/// log.append(0);
+/// log.append(1);
+///
+/// assert_eq!(log.len(), 2);
+/// assert_eq!(log.witness(1).reconstruct(), log.root_hash());
+/// ```
+#[derive(Default, Eq, PartialEq, Clone, Debug)]
+pub struct MerkleLog<T> {
+    hash: Hash,
+    items: Vec<T>,
+    /// `levels[h][i]` is the hash of the complete subtree that covers the items in
+    /// `[i * 2^h, (i + 1) * 2^h)`, the leaf hashes are stored in `levels[0]`.
+    levels: Vec<Vec<Hash>>,
+}
+
+impl<T> MerkleLog<T> {
+    /// Create a new, empty MerkleLog<T>
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            hash: [0; 32],
+            items: Vec::new(),
+            levels: Vec::new(),
+        }
+    }
+
+    /// Construct a new, empty MerkleLog<T> with the specified capacity.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            hash: [0; 32],
+            items: Vec::with_capacity(capacity),
+            levels: Vec::new(),
+        }
+    }
+
+    /// Returns `true` if the log does not have any elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the number of elements in the log, also referred to as its ‘length’.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns a reference to the element at the given index, or `None` if the index
+    /// is out of bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.items.get(index)
+    }
+
+    /// Returns an iterator over the data.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.items.iter()
+    }
+
+    /// Return the underlying vector containing the items.
+    #[inline]
+    pub fn as_vec(&self) -> &Vec<T> {
+        &self.items
+    }
+
+    /// Returns the root hash of the log as it was when it only contained the first
+    /// `size` elements.
+    ///
+    /// # Panics
+    ///
+    /// If `size` is larger than the current length of the log.
+    pub fn root_hash_at(&self, size: usize) -> Hash {
+        assert!(size <= self.len(), "MerkleLog: size out of bounds.");
+
+        if size == 0 {
+            HashTree::Empty.reconstruct()
+        } else {
+            self.subtree_hash(0, size)
+        }
+    }
+
+    /// Generate the consistency proof between the version of the log with `old_size`
+    /// elements and the one with `new_size` elements, the proof can be checked using
+    /// [`verify_consistency`] against the root hash of both versions.
+    ///
+    /// # Panics
+    ///
+    /// If `old_size` is larger than `new_size` or `new_size` is larger than the
+    /// current length of the log.
+    pub fn consistency_proof(&self, old_size: usize, new_size: usize) -> Vec<Hash> {
+        assert!(old_size <= new_size, "MerkleLog: invalid range.");
+        assert!(new_size <= self.len(), "MerkleLog: size out of bounds.");
+
+        let mut proof = Vec::new();
+
+        if old_size > 0 && old_size < new_size {
+            self.sub_proof(old_size, 0, new_size, true, &mut proof);
+        }
+
+        proof
+    }
+
+    /// Return the hash of the subtree covering the items in `[start, end)`, the range
+    /// must be one that shows up when walking down the tree, i.e `start` is aligned
+    /// to the largest power of two smaller than `end - start`.
+    fn subtree_hash(&self, start: usize, end: usize) -> Hash {
+        let size = end - start;
+
+        if size.is_power_of_two() {
+            let height = size.trailing_zeros() as usize;
+            return self.levels[height][start >> height];
+        }
+
+        let k = split_point(size);
+        fork_hash(
+            &self.subtree_hash(start, start + k),
+            &self.subtree_hash(start + k, end),
+        )
+    }
+
+    /// The `SUBPROOF` routine from RFC 6962 section 2.1.2.
+    fn sub_proof(&self, m: usize, start: usize, end: usize, complete: bool, proof: &mut Vec<Hash>) {
+        let size = end - start;
+
+        if m == size {
+            if !complete {
+                proof.push(self.subtree_hash(start, end));
+            }
+            return;
+        }
+
+        let k = split_point(size);
+
+        if m <= k {
+            self.sub_proof(m, start, start + k, complete, proof);
+            proof.push(self.subtree_hash(start + k, end));
+        } else {
+            self.sub_proof(m - k, start + k, end, false, proof);
+            proof.push(self.subtree_hash(start, start + k));
+        }
+    }
+}
+
+impl<T: AsHashTree> MerkleLog<T> {
+    /// Append a new item to the log and update the root hash.
+    pub fn append(&mut self, item: T) {
+        let mut hash = item.root_hash();
+        self.items.push(item);
+
+        for height in 0.. {
+            if self.levels.len() == height {
+                self.levels.push(Vec::new());
+            }
+
+            let level = &mut self.levels[height];
+            level.push(hash);
+
+            if level.len() % 2 == 1 {
+                break;
+            }
+
+            hash = fork_hash(&level[level.len() - 2], &level[level.len() - 1]);
+        }
+
+        self.hash = self.subtree_hash(0, self.items.len());
+    }
+
+    /// Create a [`HashTree`] that proves the inclusion of the element at the given
+    /// index in the current version of the log.
+    ///
+    /// # Panics
+    ///
+    /// If the index is out of bounds.
+    #[must_use = "This method does not have any effects on the log."]
+    pub fn witness(&self, index: usize) -> HashTree<'_> {
+        assert!(index < self.len(), "MerkleLog: index out of bounds.");
+        self.witness_in(index, 0, self.len())
+    }
+
+    fn witness_in(&self, index: usize, start: usize, end: usize) -> HashTree<'_> {
+        let size = end - start;
+
+        if size == 1 {
+            return self.items[start].as_hash_tree();
+        }
+
+        let k = split_point(size);
+
+        if index < start + k {
+            fork(
+                self.witness_in(index, start, start + k),
+                HashTree::Pruned(self.subtree_hash(start + k, end)),
+            )
+        } else {
+            fork(
+                HashTree::Pruned(self.subtree_hash(start, start + k)),
+                self.witness_in(index, start + k, end),
+            )
+        }
+    }
+
+    fn tree_in(&self, start: usize, end: usize) -> HashTree<'_> {
+        let size = end - start;
+
+        if size == 1 {
+            return self.items[start].as_hash_tree();
+        }
+
+        let k = split_point(size);
+        fork(self.tree_in(start, start + k), self.tree_in(start + k, end))
+    }
+}
+
+/// Verify a consistency proof generated by [`MerkleLog::consistency_proof`], returns
+/// `true` if the log with root hash `new_root` is an extension of the one with root
+/// hash `old_root`.
+///
+/// This is the verification algorithm described in RFC 9162 section 2.1.4.2.
+pub fn verify_consistency(
+    old_size: usize,
+    old_root: &Hash,
+    new_size: usize,
+    new_root: &Hash,
+    proof: &[Hash],
+) -> bool {
+    if old_size > new_size {
+        return false;
+    }
+
+    if old_size == new_size {
+        return proof.is_empty() && old_root == new_root;
+    }
+
+    if old_size == 0 {
+        return proof.is_empty();
+    }
+
+    let mut path = Vec::with_capacity(proof.len() + 1);
+    if old_size.is_power_of_two() {
+        path.push(*old_root);
+    }
+    path.extend_from_slice(proof);
+
+    if path.is_empty() {
+        return false;
+    }
+
+    let mut fn_ = old_size - 1;
+    let mut sn = new_size - 1;
+
+    while fn_ & 1 == 1 {
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+
+    let mut fr = path[0];
+    let mut sr = path[0];
+
+    for c in &path[1..] {
+        if sn == 0 {
+            return false;
+        }
+
+        if fn_ & 1 == 1 || fn_ == sn {
+            fr = fork_hash(c, &fr);
+            sr = fork_hash(c, &sr);
+
+            while fn_ & 1 == 0 && fn_ != 0 {
+                fn_ >>= 1;
+                sn >>= 1;
+            }
+        } else {
+            sr = fork_hash(&sr, c);
+        }
+
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+
+    &fr == old_root && &sr == new_root && sn == 0
+}
+
+/// Returns the largest power of two smaller than `n`, `n` must be larger than one.
+#[inline]
+fn split_point(n: usize) -> usize {
+    debug_assert!(n > 1);
+    1 << (usize::BITS - 1 - (n - 1).leading_zeros())
+}
+
+impl<T: AsHashTree> AsHashTree for MerkleLog<T> {
+    #[inline]
+    fn root_hash(&self) -> Hash {
+        if self.is_empty() {
+            HashTree::Empty.reconstruct()
+        } else {
+            self.hash
+        }
+    }
+
+    fn as_hash_tree(&self) -> HashTree<'_> {
+        if self.is_empty() {
+            HashTree::Empty
+        } else {
+            self.tree_in(0, self.len())
+        }
+    }
+}
+
+impl<T: AsHashTree> FromIterator<T> for MerkleLog<T> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut log = MerkleLog::new();
+        log.extend(iter);
+        log
+    }
+}
+
+impl<T: AsHashTree> From<Vec<T>> for MerkleLog<T> {
+    #[inline]
+    fn from(items: Vec<T>) -> Self {
+        items.into_iter().collect()
+    }
+}
+
+impl<T: AsHashTree> From<MerkleLog<T>> for Vec<T> {
+    #[inline]
+    fn from(log: MerkleLog<T>) -> Self {
+        log.items
+    }
+}
+
+impl<T: AsHashTree> Extend<T> for MerkleLog<T> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.append(item);
+        }
+    }
+}
+
+impl<T: AsHashTree> Index<usize> for MerkleLog<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &Self::Output {
+        self.items.index(index)
+    }
+}
+
+impl<T: Serialize + AsHashTree> Serialize for MerkleLog<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.items.serialize(serializer)
+    }
+}
+
+impl<'de, T: AsHashTree + Deserialize<'de>> Deserialize<'de> for MerkleLog<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(<Vec<T>>::deserialize(deserializer)?.into())
+    }
+}
+
+impl<T: CandidType> CandidType for MerkleLog<T> {
+    fn _ty() -> Type {
+        <Vec<T>>::_ty()
+    }
+
+    fn idl_serialize<S>(&self, serializer: S) -> Result<(), S::Error>
+    where
+        S: candid::types::Serializer,
+    {
+        self.items.idl_serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn append() {
+        let mut log = MerkleLog::<usize>::with_capacity(100);
+        let mut hash = log.root_hash();
+        assert_eq!(log.is_empty(), true);
+        assert_eq!(hash, HashTree::Empty.reconstruct());
+
+        for i in 0..100 {
+            log.append(i);
+            assert_eq!(log.len(), i + 1);
+            let new_hash = log.root_hash();
+            assert_ne!(hash, new_hash);
+            assert_eq!(new_hash, log.as_hash_tree().reconstruct());
+            hash = new_hash;
+        }
+
+        assert_eq!((0..100).collect::<MerkleLog<_>>().root_hash(), hash);
+    }
+
+    #[test]
+    fn single_entry() {
+        let log = (0..1).collect::<MerkleLog<usize>>();
+        assert_eq!(log.root_hash(), 0usize.root_hash());
+        assert_eq!(log.witness(0), 0usize.as_hash_tree());
+    }
+
+    #[test]
+    fn inclusion() {
+        let log = (0..100).collect::<MerkleLog<usize>>();
+
+        for i in 0..100 {
+            let witness = log.witness(i);
+            assert_eq!(witness.reconstruct(), log.root_hash());
+            assert_eq!(witness.get_leaf_values(), vec![&i.to_be_bytes()[..]]);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn inclusion_out_of_range() {
+        let log = (0..10).collect::<MerkleLog<usize>>();
+        let _ = log.witness(10);
+    }
+
+    #[test]
+    fn historical_root() {
+        let log = (0..50).collect::<MerkleLog<usize>>();
+
+        for size in 0..=50 {
+            let expected = (0..size).collect::<MerkleLog<usize>>().root_hash();
+            assert_eq!(log.root_hash_at(size), expected);
+        }
+    }
+
+    #[test]
+    fn consistency() {
+        let log = (0..40).collect::<MerkleLog<usize>>();
+
+        for old_size in 0..=40 {
+            for new_size in old_size..=40 {
+                let old_root = log.root_hash_at(old_size);
+                let new_root = log.root_hash_at(new_size);
+                let proof = log.consistency_proof(old_size, new_size);
+
+                assert!(verify_consistency(
+                    old_size, &old_root, new_size, &new_root, &proof
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn consistency_invalid() {
+        let log = (0..20).collect::<MerkleLog<usize>>();
+        let other = (100..120).collect::<MerkleLog<usize>>();
+
+        let old_root = log.root_hash_at(7);
+        let new_root = log.root_hash_at(13);
+        let proof = log.consistency_proof(7, 13);
+
+        assert!(!verify_consistency(
+            7,
+            &other.root_hash_at(7),
+            13,
+            &new_root,
+            &proof
+        ));
+        assert!(!verify_consistency(
+            7,
+            &old_root,
+            13,
+            &other.root_hash_at(13),
+            &proof
+        ));
+        assert!(!verify_consistency(6, &old_root, 13, &new_root, &proof));
+        assert!(!verify_consistency(13, &new_root, 7, &old_root, &proof));
+        assert!(!verify_consistency(
+            7,
+            &old_root,
+            13,
+            &new_root,
+            &proof[1..]
+        ));
+    }
+
+    #[test]
+    fn serde_cbor() {
+        let log = (0..10).collect::<MerkleLog<_>>();
+        let serialized = serde_cbor::to_vec(&log).unwrap();
+        let actual: MerkleLog<i32> = serde_cbor::from_slice(&serialized).unwrap();
+        assert_eq!(actual.root_hash(), log.root_hash());
+        assert_eq!(actual, log);
+    }
+}
@@ -1,6 +1,8 @@
 //! Useful collections that implement [`crate::AsHashTree`]
 
 pub mod group;
+pub mod lru_map;
 pub mod map;
+pub mod merkle_log;
 pub mod paged;
 pub mod seq;
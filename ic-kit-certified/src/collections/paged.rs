@@ -3,6 +3,7 @@ use crate::{AsHashTree, Hash, HashTree, Map, Seq};
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
 use std::borrow::{Borrow, Cow};
+use std::cmp::Ordering;
 
 #[derive(CandidType, Serialize, Deserialize, Debug)]
 pub struct Paged<K: Label + Ord + 'static, V: AsHashTree + 'static, const S: usize> {
@@ -22,6 +23,13 @@ impl<K: Label + Ord + 'static> Label for PagedKey<K> {
         data.extend_from_slice(&self.page.to_be_bytes());
         Cow::Owned(data)
     }
+
+    #[inline]
+    fn cmp_label(&self, other: &Self) -> Ordering {
+        self.key
+            .cmp_label(&other.key)
+            .then_with(|| self.page.cmp(&other.page))
+    }
 }
 
 impl<K: Label + Ord + 'static> Borrow<K> for PagedKey<K> {
@@ -93,8 +101,9 @@ impl<K: Label + Ord + 'static, V: AsHashTree + 'static, const S: usize> Paged<K,
 
     pub fn get(&self, key: &K, page: usize) -> Option<&Seq<V>> {
         let page = page as u32;
-        let key = (key, page);
-        self.data.inner.get_with(|k| key.cmp(&(&k.key, k.page)))
+        self.data
+            .inner
+            .get_with(|k| key.cmp_label(&k.key).then_with(|| page.cmp(&k.page)))
     }
 
     // TODO(qti3e) Remove the Clone in future.
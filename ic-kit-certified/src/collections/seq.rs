@@ -10,6 +10,11 @@ use std::slice::{Iter, SliceIndex};
 
 /// An append only list of `T`.
 ///
+/// The hash of the sequence is a hash chain over the root hash of the items, so the
+/// sequence is always represented as a single pruned node in the hash tree and it is
+/// not possible to create a witness for one of its items, use [`crate::MerkleLog`]
+/// when a proof for each entry is needed.
+///
 /// # Example
 ///
 /// ```
@@ -115,6 +120,22 @@ impl<T: AsHashTree> Seq<T> {
         self.items.iter()
     }
 
+    /// Returns a reference to the element at the given index, or `None` if the index is
+    /// out of bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.items.get(index)
+    }
+
+    /// Returns the elements in the range `start..end`, the range is clamped to the bounds
+    /// of the sequence, so this method never panics.
+    #[inline]
+    pub fn range(&self, start: usize, end: usize) -> &[T] {
+        let end = end.min(self.items.len());
+        let start = start.min(end);
+        &self.items[start..end]
+    }
+
     /// Recompute the hash of the sequence.
     #[inline]
     fn recompute_hash(&mut self, prev_len: usize) {
@@ -359,6 +380,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn get_and_range() {
+        let seq = (0..10).collect::<Seq<_>>();
+
+        assert_eq!(seq.get(0), Some(&0));
+        assert_eq!(seq.get(9), Some(&9));
+        assert_eq!(seq.get(10), None);
+
+        assert_eq!(seq.range(2, 5), &[2, 3, 4]);
+        assert_eq!(seq.range(8, 100), &[8, 9]);
+        assert_eq!(seq.range(20, 30), &[] as &[i32]);
+        assert_eq!(seq.range(5, 2), &[] as &[i32]);
+    }
+
     #[test]
     #[should_panic]
     fn index_out_of_range() {
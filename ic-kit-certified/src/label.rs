@@ -1,5 +1,6 @@
 use candid::Principal;
 use std::borrow::{Borrow, Cow};
+use std::cmp::Ordering;
 use std::ptr::NonNull;
 use std::rc::Rc;
 use std::sync::Arc;
@@ -11,6 +12,18 @@ use std::sync::Arc;
 /// [`RbTree`]: crate::rbtree::RbTree
 pub trait Label: Ord {
     fn as_label(&self) -> Cow<[u8]>;
+
+    /// Compare two labels, this is the order in which the keys are stored in the [`RbTree`]
+    /// and it must be the same as the byte-wise order of the labels, since the keys in a
+    /// [`HashTree`] should be sorted. The default implementation compares the result of
+    /// [`Label::as_label`], it can be overridden when a cheaper comparison gives the same order.
+    ///
+    /// [`HashTree`]: crate::HashTree
+    /// [`RbTree`]: crate::rbtree::RbTree
+    #[inline]
+    fn cmp_label(&self, other: &Self) -> Ordering {
+        self.as_label().cmp(&other.as_label())
+    }
 }
 
 /// A type `T` can be defined as prefix of type `U`, if they follow the same
@@ -25,7 +38,7 @@ pub trait Label: Ord {
 /// 3. `u0 == u1 => x0 == x1`
 ///
 /// To implement this type, the Self (i.e `U`) should be borrowable as a `T`.
-pub trait Prefix<T: Ord + ?Sized>: Label + Borrow<T> {
+pub trait Prefix<T: Label + ?Sized>: Label + Borrow<T> {
     /// Check if the provided value is the prefix of self. The default
     /// implementation only extracts the prefix from Self and checks
     /// for their equality which might not be true for some cases
@@ -35,6 +48,12 @@ pub trait Prefix<T: Ord + ?Sized>: Label + Borrow<T> {
     }
 }
 
+impl Label for [u8] {
+    fn as_label(&self) -> Cow<[u8]> {
+        Cow::Borrowed(self)
+    }
+}
+
 impl Label for Vec<u8> {
     fn as_label(&self) -> Cow<[u8]> {
         Cow::Borrowed(self)
@@ -65,6 +84,12 @@ impl Label for Principal {
     }
 }
 
+impl Label for str {
+    fn as_label(&self) -> Cow<[u8]> {
+        Cow::Borrowed(self.as_bytes())
+    }
+}
+
 impl Label for String {
     fn as_label(&self) -> Cow<[u8]> {
         Cow::Borrowed(self.as_bytes())
@@ -95,6 +120,11 @@ where
     fn as_label(&self) -> Cow<[u8]> {
         self.as_ref().as_label()
     }
+
+    #[inline]
+    fn cmp_label(&self, other: &Self) -> Ordering {
+        self.as_ref().cmp_label(other.as_ref())
+    }
 }
 
 impl<T> Label for Rc<T>
@@ -105,6 +135,11 @@ where
     fn as_label(&self) -> Cow<[u8]> {
         self.as_ref().as_label()
     }
+
+    #[inline]
+    fn cmp_label(&self, other: &Self) -> Ordering {
+        self.as_ref().cmp_label(other.as_ref())
+    }
 }
 
 impl<T> Label for Arc<T>
@@ -115,6 +150,11 @@ where
     fn as_label(&self) -> Cow<[u8]> {
         self.as_ref().as_label()
     }
+
+    #[inline]
+    fn cmp_label(&self, other: &Self) -> Ordering {
+        self.as_ref().cmp_label(other.as_ref())
+    }
 }
 
 impl<T> Label for NonNull<T>
@@ -125,6 +165,11 @@ where
     fn as_label(&self) -> Cow<[u8]> {
         unsafe { self.as_ref().as_label() }
     }
+
+    #[inline]
+    fn cmp_label(&self, other: &Self) -> Ordering {
+        unsafe { self.as_ref().cmp_label(other.as_ref()) }
+    }
 }
 
 macro_rules! impl_fixed_size {
@@ -164,4 +209,24 @@ macro_rules! impl_num {
     }
 }
 
-impl_num!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize);
+/// The big-endian bytes of an unsigned integer are sorted the same way as the integer itself, so
+/// the labels can be compared without encoding them.
+macro_rules! impl_unsigned_num {
+    ( $($name:ty),* ) => {
+        $(
+            impl Label for $name {
+                fn as_label(&self) -> Cow<[u8]> {
+                    Cow::Owned(self.to_be_bytes().into())
+                }
+
+                #[inline]
+                fn cmp_label(&self, other: &Self) -> Ordering {
+                    self.cmp(other)
+                }
+            }
+        )*
+    }
+}
+
+impl_num!(i8, i16, i32, i64, i128, isize);
+impl_unsigned_num!(u8, u16, u32, u64, u128, usize);
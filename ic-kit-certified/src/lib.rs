@@ -7,7 +7,9 @@ pub mod rbtree;
 pub use as_hash_tree::AsHashTree;
 pub use collections::group::builder::GroupBuilder;
 pub use collections::group::Group;
+pub use collections::lru_map::LruMap;
 pub use collections::map::Map;
+pub use collections::merkle_log::MerkleLog;
 pub use collections::paged::Paged;
 pub use collections::seq::Seq;
 pub use hashtree::{Hash, HashTree};
@@ -75,13 +75,13 @@ impl<'a, T: Label> Eq for KeyBound<'a, T> {}
 
 impl<'a, T: Label> PartialOrd<Self> for KeyBound<'a, T> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.as_ref().partial_cmp(other.as_ref())
+        Some(self.cmp(other))
     }
 }
 
 impl<'a, T: Label> Ord for KeyBound<'a, T> {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.as_ref().cmp(other.as_ref())
+        self.as_ref().cmp_label(other.as_ref())
     }
 }
 
@@ -300,12 +300,12 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
     pub fn get<Q: ?Sized>(&self, key: &Q) -> Option<&V>
     where
         K: Borrow<Q>,
-        Q: Ord,
+        Q: Label,
     {
         unsafe {
             let mut root = self.root;
             while !root.is_null() {
-                match key.cmp((*root).key.borrow()) {
+                match key.cmp_label((*root).key.borrow()) {
                     Equal => return Some(&(*root).value),
                     Less => root = (*root).left,
                     Greater => root = (*root).right,
@@ -334,7 +334,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
     unsafe fn get_node(&self, key: &K) -> *mut Node<K, V> {
         let mut root = self.root;
         while !root.is_null() {
-            match key.cmp(&(*root).key) {
+            match key.cmp_label(&(*root).key) {
                 Equal => return root,
                 Less => root = (*root).left,
                 Greater => root = (*root).right,
@@ -348,7 +348,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
     pub fn modify<'a, Q: ?Sized, T>(&mut self, key: &Q, f: impl FnOnce(&'a mut V) -> T) -> Option<T>
     where
         K: Borrow<Q>,
-        Q: Ord,
+        Q: Label,
     {
         unsafe fn go<'a, K: 'static + Label, V: AsHashTree + 'static, T, Q: ?Sized>(
             mut h: *mut Node<K, V>,
@@ -357,13 +357,13 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
         ) -> Option<T>
         where
             K: Borrow<Q>,
-            Q: Ord,
+            Q: Label,
         {
             if h.is_null() {
                 return None;
             }
 
-            match k.cmp((*h).key.borrow()) {
+            match k.cmp_label((*h).key.borrow()) {
                 Equal => {
                     let res = f(&mut (*h).value);
                     (*h).subtree_hash = Node::subtree_hash(h);
@@ -392,7 +392,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
     ) -> Option<T>
     where
         K: Prefix<P>,
-        P: Ord,
+        P: Label,
     {
         unsafe fn go<
             'a,
@@ -408,7 +408,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
         ) -> (Option<T>, Option<F>)
         where
             K: Prefix<P>,
-            P: Ord,
+            P: Label,
         {
             if h.is_null() {
                 return (None, Some(f));
@@ -417,7 +417,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
             let node_key = &(*h).key;
             let key_prefix = node_key.borrow();
 
-            let res = match key_prefix.cmp(prefix) {
+            let res = match key_prefix.cmp_label(prefix) {
                 Greater | Equal if node_key.is_prefix(prefix) => match go((*h).right, prefix, f) {
                     (None, Some(f)) => {
                         let ret = f(node_key, &mut (*h).value);
@@ -442,7 +442,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
     pub fn max_entry_with_prefix<P: ?Sized>(&self, prefix: &P) -> Option<(&K, &V)>
     where
         K: Prefix<P>,
-        P: Ord,
+        P: Label,
     {
         unsafe fn go<'a, K: 'static + Label, V, P: ?Sized>(
             n: *mut Node<K, V>,
@@ -450,7 +450,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
         ) -> Option<(&'a K, &'a V)>
         where
             K: Prefix<P>,
-            P: Ord,
+            P: Label,
         {
             if n.is_null() {
                 return None;
@@ -458,7 +458,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
 
             let node_key = &(*n).key;
             let key_prefix = node_key.borrow();
-            match key_prefix.cmp(prefix) {
+            match key_prefix.cmp_label(prefix) {
                 Greater | Equal if node_key.is_prefix(prefix) => {
                     go((*n).right, prefix).or(Some((node_key, &(*n).value)))
                 }
@@ -492,7 +492,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
     pub fn witness<Q: ?Sized>(&self, key: &Q) -> HashTree<'_>
     where
         K: Borrow<Q>,
-        Q: Ord,
+        Q: Label,
     {
         self.nested_witness(key, |v| v.as_hash_tree())
     }
@@ -508,7 +508,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
     ) -> HashTree<'a>
     where
         K: Borrow<Q>,
-        Q: Ord,
+        Q: Label,
     {
         if let Some(t) = self.lookup_and_build_witness(key, f) {
             return t;
@@ -535,8 +535,8 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
     pub fn key_range<Q1: ?Sized, Q2: ?Sized>(&self, first: &Q1, last: &Q2) -> HashTree<'_>
     where
         K: Borrow<Q1> + Borrow<Q2>,
-        Q1: Ord,
-        Q2: Ord,
+        Q1: Label,
+        Q2: Label,
     {
         self.range_witness(
             self.lower_bound(first),
@@ -551,8 +551,8 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
     pub fn value_range<Q1: ?Sized, Q2: ?Sized>(&self, first: &Q1, last: &Q2) -> HashTree<'_>
     where
         K: Borrow<Q1> + Borrow<Q2>,
-        Q1: Ord,
-        Q2: Ord,
+        Q1: Label,
+        Q2: Label,
     {
         self.range_witness(
             self.lower_bound(first),
@@ -567,7 +567,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
     pub fn keys_with_prefix<P: ?Sized>(&self, prefix: &P) -> HashTree<'_>
     where
         K: Prefix<P>,
-        P: Ord,
+        P: Label,
     {
         self.range_witness(
             self.lower_bound(prefix),
@@ -616,7 +616,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
             if n.is_null() {
                 return Empty;
             }
-            match (*n).key.cmp(lo.as_ref()) {
+            match (*n).key.cmp_label(lo.as_ref()) {
                 Equal => three_way_fork(
                     Node::left_hash_tree(n),
                     match lo {
@@ -653,7 +653,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
             if n.is_null() {
                 return Empty;
             }
-            match (*n).key.cmp(hi.as_ref()) {
+            match (*n).key.cmp_label(hi.as_ref()) {
                 Equal => three_way_fork(
                     Node::full_witness_tree((*n).left, f),
                     match hi {
@@ -684,7 +684,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
         f: unsafe fn(*mut Node<K, V>) -> HashTree<'a>,
     ) -> HashTree<'a> {
         debug_assert!(
-            lo.as_ref() <= hi.as_ref(),
+            lo.as_ref().cmp_label(hi.as_ref()) != Greater,
             "lo = {:?} > hi = {:?}",
             lo.as_ref().as_label(),
             hi.as_ref().as_label()
@@ -699,7 +699,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
                 return Empty;
             }
             let k = &(*n).key;
-            match (lo.as_ref().cmp(k), k.cmp(hi.as_ref())) {
+            match (lo.as_ref().cmp_label(k), k.cmp_label(hi.as_ref())) {
                 (Less, Less) => {
                     let left = go((*n).left, lo, hi, f);
                     let right = go((*n).right, lo, hi, f);
@@ -749,7 +749,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
     fn lower_bound<Q: ?Sized>(&self, key: &Q) -> Option<KeyBound<'_, K>>
     where
         K: Borrow<Q>,
-        Q: Ord,
+        Q: Label,
     {
         unsafe fn go<'a, K: 'static + Label, V, Q: ?Sized>(
             n: *mut Node<K, V>,
@@ -757,13 +757,13 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
         ) -> Option<KeyBound<'a, K>>
         where
             K: Borrow<Q>,
-            Q: Ord,
+            Q: Label,
         {
             if n.is_null() {
                 return None;
             }
             let node_key = &(*n).key;
-            match node_key.borrow().cmp(key) {
+            match node_key.borrow().cmp_label(key) {
                 Less => go((*n).right, key).or(Some(KeyBound::Neighbor(node_key))),
                 Equal => Some(KeyBound::Exact(node_key)),
                 Greater => go((*n).left, key),
@@ -775,7 +775,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
     fn upper_bound<Q: ?Sized>(&self, key: &Q) -> Option<KeyBound<'_, K>>
     where
         K: Borrow<Q>,
-        Q: Ord,
+        Q: Label,
     {
         unsafe fn go<'a, K: 'static + Label, V, Q: ?Sized>(
             n: *mut Node<K, V>,
@@ -783,13 +783,13 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
         ) -> Option<KeyBound<'a, K>>
         where
             K: Borrow<Q>,
-            Q: Ord,
+            Q: Label,
         {
             if n.is_null() {
                 return None;
             }
             let node_key = &(*n).key;
-            match node_key.borrow().cmp(key) {
+            match node_key.borrow().cmp_label(key) {
                 Less => go((*n).right, key),
                 Equal => Some(KeyBound::Exact(node_key)),
                 Greater => go((*n).left, key).or(Some(KeyBound::Neighbor(node_key))),
@@ -801,7 +801,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
     fn right_prefix_neighbor<P: ?Sized>(&self, prefix: &P) -> Option<KeyBound<'_, K>>
     where
         K: Prefix<P>,
-        P: Ord,
+        P: Label,
     {
         unsafe fn go<'a, K: 'static + Label, V, P: ?Sized>(
             n: *mut Node<K, V>,
@@ -809,14 +809,14 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
         ) -> Option<KeyBound<'a, K>>
         where
             K: Prefix<P>,
-            P: Ord,
+            P: Label,
         {
             if n.is_null() {
                 return None;
             }
             let node_key = &(*n).key;
             let key_prefix = node_key.borrow();
-            match key_prefix.cmp(prefix) {
+            match key_prefix.cmp_label(prefix) {
                 Greater if node_key.is_prefix(prefix) => go((*n).right, prefix),
                 Greater => go((*n).left, prefix).or(Some(KeyBound::Neighbor(node_key))),
                 Less | Equal => go((*n).right, prefix),
@@ -832,7 +832,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
     ) -> Option<HashTree<'a>>
     where
         K: Borrow<Q>,
-        Q: Ord,
+        Q: Label,
     {
         unsafe fn go<'a, K: 'static + Label, V: AsHashTree + 'static, Q: ?Sized>(
             n: *mut Node<K, V>,
@@ -841,12 +841,12 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
         ) -> Option<HashTree<'a>>
         where
             K: Borrow<Q>,
-            Q: Ord,
+            Q: Label,
         {
             if n.is_null() {
                 return None;
             }
-            match key.cmp((*n).key.borrow()) {
+            match key.cmp_label((*n).key.borrow()) {
                 Equal => Some(three_way_fork(
                     Node::left_hash_tree(n),
                     Node::subtree_with(n, f),
@@ -896,7 +896,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
                 };
             }
 
-            let (old_value, new_value_ref) = match k.cmp(&(*h).key) {
+            let (old_value, new_value_ref) = match k.cmp_label(&(*h).key) {
                 Equal => {
                     std::mem::swap(&mut (*h).value, &mut v);
                     (*h).subtree_hash = Node::subtree_hash(h);
@@ -950,7 +950,7 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
     pub fn delete<Q: ?Sized>(&mut self, key: &Q) -> Option<(K, V)>
     where
         K: Borrow<Q>,
-        Q: Ord,
+        Q: Label,
     {
         unsafe fn move_red_left<K: 'static + Label, V: AsHashTree + 'static>(
             mut h: *mut Node<K, V>,
@@ -1009,9 +1009,9 @@ impl<K: 'static + Label, V: AsHashTree + 'static> RbTree<K, V> {
         ) -> *mut Node<K, V>
         where
             K: Borrow<Q>,
-            Q: Ord,
+            Q: Label,
         {
-            if key < (*h).key.borrow() {
+            if key.cmp_label((*h).key.borrow()) == Less {
                 if !is_red((*h).left) && !is_red((*(*h).left).left) {
                     h = move_red_left(h);
                 }
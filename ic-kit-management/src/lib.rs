@@ -14,3 +14,8 @@ pub struct CanisterSettings {
     pub memory_allocation: Option<Nat>,
     pub freezing_threshold: Option<Nat>,
 }
+
+#[derive(Deserialize, Debug, Clone, PartialOrd, PartialEq, CandidType)]
+pub struct CanisterIdRecord {
+    pub canister_id: Principal,
+}
@@ -18,12 +18,21 @@ use tokio::sync::oneshot;
 
 const MAX_CYCLES_PER_RESPONSE: u128 = 12;
 
+/// The maximum size of the payload of an inter-canister call on the IC.
+const MAX_INTER_CANISTER_PAYLOAD_IN_BYTES: usize = 2 * 1024 * 1024;
+
+/// The number of pages addressable by the 32-bit stable memory API, that is 4GiB.
+const MAX_STABLE_PAGES_32: u64 = 1 << 16;
+
 /// A canister that is being executed.
 pub struct Canister {
     /// The id of the canister.
     canister_id: Principal,
     /// Maps the name of each of exported methods to the task function.
     symbol_table: HashMap<String, fn()>,
+    /// The rejection sent back for calls to a method that is not exported by the canister,
+    /// when it is None a `DestinationInvalid` rejection is used.
+    missing_method_reject: Option<(RejectionCode, String)>,
     /// The data reply that is being built for the current message. An interesting thing about the
     /// IC that I did not expect: The reply data is not preserved throughout the async context.
     /// And the reply is the first call to msg_reply that is inside a non-trapping task.
@@ -50,6 +59,15 @@ pub struct Canister {
     env: Env,
     /// The stable storage backend for this canister.
     stable: Box<dyn StableMemoryBackend + Send>,
+    /// The maximum size of the arguments of an inter-canister call made by this canister,
+    /// larger calls are not performed.
+    max_payload_bytes: usize,
+    /// The data set by the canister using `certified_data_set`, at most 32 bytes.
+    certified_data: Vec<u8>,
+    /// The version of the canister returned by `canister_version`.
+    version: u64,
+    /// If set the version is incremented after every successful non-query message.
+    auto_increment_version: bool,
     /// The request id of the current incoming message.
     request_id: Option<IncomingRequestId>,
     /// The calls that are finalized and should be sent after this entry point's successful
@@ -156,6 +174,7 @@ impl Canister {
         Self {
             canister_id: canister_id.into(),
             symbol_table: HashMap::new(),
+            missing_method_reject: None,
             msg_reply_data: Vec::new(),
             msg_reply_senders: HashMap::new(),
             msg_reply: None,
@@ -165,6 +184,10 @@ impl Canister {
             outgoing_calls: HashMap::new(),
             env: Env::default(),
             stable: Box::new(HeapStableMemory::default()),
+            max_payload_bytes: MAX_INTER_CANISTER_PAYLOAD_IN_BYTES,
+            certified_data: Vec::new(),
+            version: 0,
+            auto_increment_version: false,
             request_id: None,
             call_queue: Vec::with_capacity(8),
             pending_call: None,
@@ -181,6 +204,18 @@ impl Canister {
         self.canister_id
     }
 
+    /// Return the export name of all the methods defined on this canister in sorted order, for
+    /// example `canister_update increment` or `canister_init`.
+    pub fn exported_methods(&self) -> Vec<&str> {
+        let mut methods = self
+            .symbol_table
+            .keys()
+            .map(|name| name.as_str())
+            .collect::<Vec<_>>();
+        methods.sort_unstable();
+        methods
+    }
+
     /// Provide the canister with the definition of the given method.
     pub fn with_method<M: CanisterMethod + 'static>(mut self) -> Self {
         let method_name = String::from(M::EXPORT_NAME);
@@ -194,6 +229,55 @@ impl Canister {
         self
     }
 
+    /// Provide the canister with the definition of the given method, replacing any existing
+    /// definition of a method with the same export name.
+    pub fn override_method<M: CanisterMethod + 'static>(mut self) -> Self {
+        self.symbol_table
+            .insert(String::from(M::EXPORT_NAME), M::exported_method);
+        self
+    }
+
+    /// Remove the method with the given export name from the canister, for example
+    /// `canister_update increment`. Returns `true` if the canister had such a method.
+    pub fn remove_method(&mut self, export_name: &str) -> bool {
+        self.symbol_table.remove(export_name).is_some()
+    }
+
+    /// Reject calls to the methods that are not exported by this canister with the given
+    /// rejection code and message.
+    pub fn or_reject<S: Into<String>>(mut self, rejection_code: RejectionCode, message: S) -> Self {
+        self.missing_method_reject = Some((rejection_code, message.into()));
+        self
+    }
+
+    /// Respond to the calls to methods that are not exported by this canister as if the
+    /// canister trapped with the given message.
+    pub fn or_trap<S: Into<String>>(self, message: S) -> Self {
+        self.or_reject(RejectionCode::CanisterError, message)
+    }
+
+    /// Set the maximum size of the arguments of the inter-canister calls made by this canister,
+    /// `call_perform` fails for any call with a larger payload. Defaults to the IC's limit of
+    /// 2MiB.
+    pub fn with_max_payload_bytes(mut self, max_payload_bytes: usize) -> Self {
+        self.max_payload_bytes = max_payload_bytes;
+        self
+    }
+
+    /// Set the version of the canister, which is returned by `canister_version`. Defaults to 0.
+    pub fn with_version(mut self, version: u64) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// If enabled the version of the canister is incremented after every successful message
+    /// other than queries, inspect_message and custom tasks. This is disabled by default, so
+    /// the version only changes when it is set explicitly.
+    pub fn with_auto_increment_version(mut self, auto_increment_version: bool) -> Self {
+        self.auto_increment_version = auto_increment_version;
+        self
+    }
+
     /// Provide the canister with this stable storage backend.
     pub fn with_stable(mut self, stable: Box<dyn StableMemoryBackend + Send>) -> Self {
         self.stable = stable;
@@ -295,15 +379,36 @@ impl Canister {
             }
         };
 
+        // A canister without an inspect_message hook accepts every message.
+        if task.is_none() && env.entry_mode == EntryMode::InspectMessage {
+            reply_sender
+                .unwrap()
+                .send(CallReply::Reply {
+                    data: Vec::new(),
+                    cycles_refunded: 0,
+                })
+                .expect("ic-kit-runtime: Could not send the message reply.");
+
+            return Vec::new();
+        }
+
         if task.is_none() {
             let chan = reply_sender.unwrap();
 
+            let (rejection_code, rejection_message) =
+                self.missing_method_reject.clone().unwrap_or_else(|| {
+                    (
+                        RejectionCode::DestinationInvalid,
+                        format!(
+                            "Canister does not have a '{}' method.",
+                            env.method_name.unwrap_or_default()
+                        ),
+                    )
+                });
+
             let reply = CallReply::Reject {
-                rejection_code: RejectionCode::DestinationInvalid,
-                rejection_message: format!(
-                    "Canister does not have a '{}' method.",
-                    env.method_name.unwrap_or_default()
-                ),
+                rejection_code,
+                rejection_message,
                 cycles_refunded: env.cycles_available,
             };
 
@@ -340,6 +445,12 @@ impl Canister {
                 self.maybe_final_reply(Some(m), self.env.cycles_available);
             }
             Completion::Ok => {
+                match self.env.entry_mode {
+                    EntryMode::Query | EntryMode::InspectMessage | EntryMode::CustomTask => {}
+                    _ if self.auto_increment_version => self.version += 1,
+                    _ => {}
+                }
+
                 if let Some(reply) = self.msg_reply.take() {
                     let chan = self
                         .msg_reply_senders
@@ -442,6 +553,20 @@ impl Canister {
         .expect("ic-kit-runtime: Could not send the message reply.")
     }
 
+    /// Return the data certificate for the current certified data, the runtime does not sign
+    /// anything, so the certificate is only the certified data itself.
+    fn data_certificate(&self) -> Vec<u8> {
+        self.certified_data.clone()
+    }
+
+    /// Trap if the given range is not within the current size of the stable memory.
+    fn check_stable_bounds(&mut self, offset: u64, size: u64) -> Result<(), String> {
+        match offset.checked_add(size) {
+            Some(end) if end <= self.stable.stable_size() << 16 => Ok(()),
+            _ => Err("Stable memory access out of bounds.".into()),
+        }
+    }
+
     fn discard_pending_call(&mut self) {
         if let Some(pending_call) = self.pending_call.take() {
             self.env.balance += MAX_CYCLES_PER_RESPONSE + pending_call.3;
@@ -759,7 +884,7 @@ impl Ic0CallHandlerProxy for Canister {
             }
         };
 
-        let amount = self.env.cycles_available.min(max_amount as u128);
+        let amount = self.env.cycles_available.min(max_amount as u64 as u128);
         self.env.cycles_available -= amount;
         self.cycles_accepted += amount;
         self.cycles_available_store
@@ -789,9 +914,9 @@ impl Ic0CallHandlerProxy for Canister {
             }
         };
 
-        let high = max_amount_high as u128;
-        let low = max_amount_low as u128;
-        let max_amount = high << 64 + low;
+        let high = max_amount_high as u64 as u128;
+        let low = max_amount_low as u64 as u128;
+        let max_amount = (high << 64) + low;
         let amount = self.env.cycles_available.min(max_amount);
         self.env.cycles_available -= amount;
         self.cycles_accepted += amount;
@@ -834,6 +959,35 @@ impl Ic0CallHandlerProxy for Canister {
         Ok(1)
     }
 
+    fn canister_version(&mut self) -> Result<i64, String> {
+        Ok(self.version as i64)
+    }
+
+    fn cycles_burn128(
+        &mut self,
+        amount_high: i64,
+        amount_low: i64,
+        dst: isize,
+    ) -> Result<(), String> {
+        match self.env.entry_mode {
+            EntryMode::Query | EntryMode::InspectMessage | EntryMode::CleanupCallback => {
+                return Err(format!(
+                    "cycles_burn128 can not be called from '{}'",
+                    self.env.get_entry_point_name()
+                ))
+            }
+            _ => {}
+        }
+
+        let high = amount_high as u64 as u128;
+        let low = amount_low as u64 as u128;
+        let amount = self.env.balance.min((high << 64) + low);
+        self.env.balance -= amount;
+        copy_to_canister(dst, 0, 16, &amount.to_le_bytes())?;
+
+        Ok(())
+    }
+
     fn msg_method_name_size(&mut self) -> Result<isize, String> {
         let method_name = match self.env.entry_mode {
             EntryMode::CustomTask | EntryMode::InspectMessage => self
@@ -879,8 +1033,24 @@ impl Ic0CallHandlerProxy for Canister {
     }
 
     fn accept_message(&mut self) -> Result<(), String> {
-        // TODO(qti3e) Hmm.. this has room for some thoughts.
-        todo!()
+        if self.env.entry_mode != EntryMode::InspectMessage {
+            return Err(format!(
+                "accept_message can not be called from '{}'",
+                self.env.get_entry_point_name()
+            ));
+        }
+
+        if self.msg_reply.is_some() {
+            return Err("accept_message can only be called once.".into());
+        }
+
+        // Accepting the message is reported as an empty reply.
+        self.msg_reply = Some(CallReply::Reply {
+            data: Vec::new(),
+            cycles_refunded: 0,
+        });
+
+        Ok(())
     }
 
     fn call_new(
@@ -973,7 +1143,7 @@ impl Ic0CallHandlerProxy for Canister {
             ));
         }
 
-        let amount = amount as u128;
+        let amount = amount as u64 as u128;
 
         if self.env.balance < amount {
             return Err(format!("Insufficient cycles balance."));
@@ -992,9 +1162,9 @@ impl Ic0CallHandlerProxy for Canister {
             ));
         }
 
-        let high = amount_high as u128;
-        let low = amount_low as u128;
-        let amount = high << 64 + low;
+        let high = amount_high as u64 as u128;
+        let low = amount_low as u64 as u128;
+        let amount = (high << 64) + low;
 
         if self.env.balance < amount {
             return Err(format!("Insufficient cycles balance."));
@@ -1009,10 +1179,17 @@ impl Ic0CallHandlerProxy for Canister {
     fn call_perform(&mut self) -> Result<i32, String> {
         if self.pending_call.is_none() {
             return Err(format!(
-                "call_cycles_add128 cannot be called when there is no pending call."
+                "call_perform cannot be called when there is no pending call."
             ));
         }
 
+        // Calls with a payload larger than the limit are not enqueued, and the cycles attached
+        // to them are refunded.
+        if self.pending_call.as_ref().unwrap().4.len() > self.max_payload_bytes {
+            self.discard_pending_call();
+            return Ok(RejectionCode::CanisterReject as i32);
+        }
+
         // TODO(qti3e) Implement the freezing threshold + system ability to perform call.
         // For now all of the calls go through.
 
@@ -1021,34 +1198,41 @@ impl Ic0CallHandlerProxy for Canister {
     }
 
     fn stable_size(&mut self) -> Result<i32, String> {
-        match self.stable.stable_size().try_into() {
-            Ok(size) => Ok(size),
-            Err(_) => Err("Invalid stable size".into()),
+        let size = self.stable.stable_size();
+
+        if size > MAX_STABLE_PAGES_32 {
+            return Err("stable_size can not be used when stable memory exceeds 4GiB".into());
         }
+
+        Ok(size as i32)
     }
 
     fn stable_grow(&mut self, new_pages: i32) -> Result<i32, String> {
-        let size = self.stable.stable_size() as i32;
-        let max_size = i32::max_value();
+        let size = self.stable.stable_size();
+        let new_pages = new_pages as u32 as u64;
 
-        if size + new_pages > max_size {
+        if size + new_pages > MAX_STABLE_PAGES_32 {
             Ok(-1)
         } else {
-            Ok(self.stable.stable_grow(new_pages as u64) as i32)
+            Ok(self.stable.stable_grow(new_pages) as i32)
         }
     }
 
-    fn stable_write(&mut self, _offset: i32, _src: isize, _size: isize) -> Result<(), String> {
+    fn stable_write(&mut self, offset: i32, src: isize, size: isize) -> Result<(), String> {
+        let offset = offset as u32 as u64;
+        self.check_stable_bounds(offset, size as u64)?;
         self.stable
-            .stable_write(_offset as u64, copy_from_canister(_src, _size));
+            .stable_write(offset, copy_from_canister(src, size));
 
         Ok(())
     }
 
     fn stable_read(&mut self, dst: isize, offset: i32, size: isize) -> Result<(), String> {
+        let offset = offset as u32 as u64;
+        self.check_stable_bounds(offset, size as u64)?;
         let mut buf = vec![0u8; size as usize];
-        self.stable.stable_read(offset as u64, &mut buf);
-        copy_to_canister(dst, offset as isize, size, &buf)?;
+        self.stable.stable_read(offset, &mut buf);
+        copy_to_canister(dst, 0, size, &buf)?;
         Ok(())
     }
 
@@ -1057,50 +1241,107 @@ impl Ic0CallHandlerProxy for Canister {
     }
 
     fn stable64_grow(&mut self, new_pages: i64) -> Result<i64, String> {
-        Ok(self.stable.stable_grow(new_pages as u64) as i64)
+        Ok(self.stable.stable_grow(new_pages as u64))
     }
 
     fn stable64_write(&mut self, offset: i64, src: i64, size: i64) -> Result<(), String> {
-        Ok(self.stable.stable_write(
+        self.check_stable_bounds(offset as u64, size as u64)?;
+        self.stable.stable_write(
             offset as u64,
             copy_from_canister(src as isize, size as isize),
-        ))
+        );
+
+        Ok(())
     }
 
     fn stable64_read(&mut self, dst: i64, offset: i64, size: i64) -> Result<(), String> {
+        self.check_stable_bounds(offset as u64, size as u64)?;
         let mut buf = vec![0u8; size as usize];
         self.stable.stable_read(offset as u64, &mut buf);
-        copy_to_canister(dst as isize, offset as isize, size as isize, &buf)?;
+        copy_to_canister(dst as isize, 0, size as isize, &buf)?;
         Ok(())
     }
 
-    fn certified_data_set(&mut self, _src: isize, _size: isize) -> Result<(), String> {
-        todo!()
+    fn certified_data_set(&mut self, src: isize, size: isize) -> Result<(), String> {
+        match self.env.entry_mode {
+            EntryMode::CustomTask
+            | EntryMode::Init
+            | EntryMode::PostUpgrade
+            | EntryMode::PreUpgrade
+            | EntryMode::Update
+            | EntryMode::ReplyCallback
+            | EntryMode::RejectCallback
+            | EntryMode::Heartbeat => {
+                if size < 0 {
+                    return Err("certified_data_set can not be called with a negative size.".into());
+                }
+
+                if size > 32 {
+                    return Err(format!(
+                        "certified_data_set can not be called with more than 32 bytes, got {} bytes.",
+                        size
+                    ));
+                }
+
+                self.certified_data = copy_from_canister(src, size).to_vec();
+                Ok(())
+            }
+            _ => Err(format!(
+                "certified_data_set can not be called from '{}'",
+                self.env.get_entry_point_name()
+            )),
+        }
     }
 
     fn data_certificate_present(&mut self) -> Result<i32, String> {
-        todo!()
+        // The certificate is only available in non-replicated query calls.
+        match self.env.entry_mode {
+            EntryMode::Query => Ok(1),
+            _ => Ok(0),
+        }
     }
 
     fn data_certificate_size(&mut self) -> Result<isize, String> {
-        todo!()
+        match self.env.entry_mode {
+            EntryMode::Query => Ok(self.data_certificate().len() as isize),
+            _ => Err(format!(
+                "data_certificate_size can not be called from '{}'",
+                self.env.get_entry_point_name()
+            )),
+        }
     }
 
     fn data_certificate_copy(
         &mut self,
-        _dst: isize,
-        _offset: isize,
-        _size: isize,
+        dst: isize,
+        offset: isize,
+        size: isize,
     ) -> Result<(), String> {
-        todo!()
+        match self.env.entry_mode {
+            EntryMode::Query => {
+                let data = self.data_certificate();
+                copy_to_canister(dst, offset, size, &data)?;
+                Ok(())
+            }
+            _ => Err(format!(
+                "data_certificate_copy can not be called from '{}'",
+                self.env.get_entry_point_name()
+            )),
+        }
     }
 
     fn time(&mut self) -> Result<i64, String> {
         Ok(self.env.time as i64)
     }
 
-    fn performance_counter(&mut self, _counter_type: i32) -> Result<i64, String> {
-        todo!()
+    fn performance_counter(&mut self, counter_type: i32) -> Result<i64, String> {
+        match counter_type {
+            0 | 1 => Ok(self.env.performance_counter as i64),
+            _ => Err(format!(
+                "Unknown performance counter type '{}'.",
+                counter_type
+            )),
+        }
     }
 
     fn debug_print(&mut self, src: isize, size: isize) -> Result<(), String> {
@@ -71,8 +71,17 @@ impl<'a> CanisterHandle<'a> {
         self.run_env(Env::post_upgrade()).await
     }
 
-    /// Runs the post_upgrade hook of the canister. For more customization use
-    /// [`CanisterHandle::run_env`] with [`Env::heartbeat()`].
+    /// Runs the inspect_message hook of the canister for an ingress message to the given method,
+    /// the reply is only successful if the canister accepts the message. For more customization
+    /// use [`CanisterHandle::run_env`] with [`Env::inspect_message()`].
+    pub async fn inspect_message<S: Into<String>>(&self, method_name: S) -> CallReply {
+        self.run_env(Env::inspect_message(method_name)).await
+    }
+
+    /// Runs the heartbeat hook of the canister. The returned future resolves as soon as the hook
+    /// returns, the calls made during the heartbeat are dispatched as usual afterwards and are
+    /// not awaited, they are sent before the canister processes its next message. For more
+    /// customization use [`CanisterHandle::run_env`] with [`Env::heartbeat()`].
     pub async fn heartbeat(&self) -> CallReply {
         self.run_env(Env::heartbeat()).await
     }
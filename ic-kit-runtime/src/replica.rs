@@ -16,7 +16,7 @@ use crate::call::{CallBuilder, CallReply};
 use crate::canister::Canister;
 use crate::handle::CanisterHandle;
 use crate::types::*;
-use candid::decode_one;
+use candid::{decode_one, encode_one, CandidType};
 use ic_kit_sys::types::RejectionCode;
 use ic_types::Principal;
 use std::cell::RefCell;
@@ -86,6 +86,14 @@ impl Replica {
         tmp
     }
 
+    /// Create an empty replica whose management canister seeds the `raw_rand` generator with the
+    /// given value, replicas created with the same seed produce the same random bytes.
+    pub fn with_rng_seed(seed: u64) -> Self {
+        let (sender, rx) = mpsc::unbounded_channel::<ReplicaWorkerMessage>();
+        tokio::spawn(replica_worker(sender.clone(), rx, seed));
+        Replica { sender }
+    }
+
     /// Add the given canister to this replica.
     pub fn add_canister(&self, canister: Canister) -> CanisterHandle {
         let canister_id = canister.id();
@@ -151,9 +159,7 @@ impl Replica {
 impl Default for Replica {
     /// Create an empty replica and run the start the event loop.
     fn default() -> Self {
-        let (sender, rx) = mpsc::unbounded_channel::<ReplicaWorkerMessage>();
-        tokio::spawn(replica_worker(sender.clone(), rx));
-        Replica { sender }
+        Replica::with_rng_seed(0)
     }
 }
 
@@ -161,6 +167,7 @@ impl Default for Replica {
 async fn replica_worker(
     sender: mpsc::UnboundedSender<ReplicaWorkerMessage>,
     mut rx: mpsc::UnboundedReceiver<ReplicaWorkerMessage>,
+    rng_seed: u64,
 ) {
     let mut state = ReplicaState {
         sender,
@@ -172,7 +179,11 @@ async fn replica_worker(
     // canister is actually a canister.
     {
         let (tx, rx) = mpsc::unbounded_channel();
-        tokio::spawn(management_canister_worker(state.sender.clone(), rx));
+        tokio::spawn(management_canister_worker(
+            state.sender.clone(),
+            rx,
+            rng_seed,
+        ));
         state.canisters.insert(Principal::management_canister(), tx);
     }
 
@@ -278,14 +289,19 @@ async fn perform_canister_request(
 async fn management_canister_worker(
     mut replica: mpsc::UnboundedSender<ReplicaWorkerMessage>,
     mut rx: mpsc::UnboundedReceiver<CanisterWorkerMessage>,
+    rng_seed: u64,
 ) {
+    // The state of the pseudo random generator used by raw_rand, a fixed seed makes the output
+    // the same across different runs of a test.
+    let mut rng = rng_seed;
+
     while let Some(message) = rx.recv().await {
         match message {
             CanisterWorkerMessage::Message {
                 message: CanisterMessage::Request { env, .. },
                 reply_sender: Some(sender),
             } => {
-                let reply = handle_management_method(&mut replica, env).await;
+                let reply = handle_management_method(&mut replica, &mut rng, env).await;
                 sender
                     .send(reply)
                     .expect("ic-kit-runtime: could not send management response.");
@@ -308,6 +324,7 @@ async fn management_canister_worker(
 
 async fn handle_management_method(
     replica: &mut mpsc::UnboundedSender<ReplicaWorkerMessage>,
+    rng: &mut u64,
     env: Env,
 ) -> CallReply {
     let method_name = env.method_name.unwrap();
@@ -325,12 +342,59 @@ async fn handle_management_method(
             .unwrap_or_else(|_| panic!("ic-kit-runtime: could not send message to replica"));
     }
 
+    if method_name == "create_canister" {
+        let (tx, rx) = oneshot::channel();
+
+        replica
+            .send(ReplicaWorkerMessage::CreateCanister { reply_sender: tx })
+            .unwrap_or_else(|_| panic!("ic-kit-runtime: could not send message to replica"));
+
+        let canister_id = rx
+            .await
+            .expect("ic-kit-runtime: Could not retrieve the id of the created canister.");
+
+        // The runtime does not keep a balance for a canister before its code is installed, so
+        // the cycles sent with the call are refunded instead of being credited to it.
+        return CallReply::Reply {
+            data: encode_one(CanisterIdRecord { canister_id }).unwrap(),
+            cycles_refunded: env.cycles_available,
+        };
+    }
+
+    if method_name == "raw_rand" {
+        let bytes = (0..4)
+            .flat_map(|_| splitmix64(rng).to_le_bytes())
+            .collect::<Vec<u8>>();
+
+        return CallReply::Reply {
+            data: encode_one(bytes).unwrap(),
+            cycles_refunded: 0,
+        };
+    }
+
     CallReply::Reply {
         data: vec![],
         cycles_refunded: 0,
     }
 }
 
+/// Advance the given SplitMix64 state and return the next pseudo random number.
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// The response of the management canister's `create_canister` method, this mirrors the public
+/// `CanisterIdRecord` in ic-kit-management which can not be used here since that crate depends on
+/// ic-kit, and so on this runtime. The two must be kept in sync.
+#[derive(CandidType)]
+struct CanisterIdRecord {
+    canister_id: Principal,
+}
+
 impl ReplicaState {
     /// Return the first unused canister id.
     fn get_next_canister_id(&mut self) -> Principal {
@@ -339,7 +403,7 @@ impl ReplicaState {
         loop {
             let canister_id = canister_id(id);
 
-            if !self.canisters.contains_key(&canister_id) {
+            if !self.canisters.contains_key(&canister_id) && !self.created.contains(&canister_id) {
                 break canister_id;
             }
 
@@ -64,6 +64,9 @@ pub struct Env {
     pub rejection_message: String,
     /// The current time in nanoseconds.
     pub time: u64,
+    /// The value returned by the instruction counter, the runtime does not count the executed
+    /// instructions.
+    pub performance_counter: u64,
 }
 
 pub type TaskFn = Box<dyn FnOnce() + Send + RefUnwindSafe + UnwindSafe>;
@@ -136,6 +139,7 @@ impl Default for Env {
             rejection_code: RejectionCode::NoError,
             rejection_message: String::new(),
             time: now(),
+            performance_counter: 0,
         }
     }
 }
@@ -170,6 +174,14 @@ impl Env {
         Self::default().with_entry_mode(EntryMode::PostUpgrade)
     }
 
+    /// Create a new env for a call to the inspect_message function, for an ingress message to
+    /// the given method.
+    pub fn inspect_message<S: Into<String>>(method_name: S) -> Self {
+        Self::default()
+            .with_entry_mode(EntryMode::InspectMessage)
+            .with_method_name(method_name)
+    }
+
     /// Create a new env for a call to the heartbeat function.
     pub fn heartbeat() -> Self {
         Self::default().with_entry_mode(EntryMode::Heartbeat)
@@ -187,6 +199,12 @@ impl Env {
         self
     }
 
+    /// Use the provided value as the instruction counters for this env.
+    pub fn with_performance_counter(mut self, instructions: u64) -> Self {
+        self.performance_counter = instructions;
+        self
+    }
+
     /// Use the given entry mode in this env.
     pub fn with_entry_mode(mut self, mode: EntryMode) -> Self {
         self.entry_mode = mode;
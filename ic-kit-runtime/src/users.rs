@@ -6,7 +6,14 @@ use lazy_static::lazy_static;
 lazy_static! {
     pub static ref ALICE: Principal = Principal::self_authenticating("ALICE");
     pub static ref BOB: Principal = Principal::self_authenticating("BOB");
+    pub static ref CHARLIE: Principal = Principal::self_authenticating("CHARLIE");
     pub static ref JOHN: Principal = Principal::self_authenticating("JOHN");
     pub static ref PARSA: Principal = Principal::self_authenticating("PARSA");
     pub static ref OZ: Principal = Principal::self_authenticating("OZ");
 }
+
+/// Return the mock principal id of the n-th user, the same index always results in the same
+/// principal id and different indices never collide with each other or the named users.
+pub fn user(n: u64) -> Principal {
+    Principal::self_authenticating(format!("USER-{}", n))
+}
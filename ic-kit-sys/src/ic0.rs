@@ -264,6 +264,8 @@ ic0_module! {
     ic0.canister_cycle_balance : () -> i64;                                            // *
     ic0.canister_cycle_balance128 : (dst : isize) -> ();                               // *
     ic0.canister_status : () -> i32;                                                   // *
+    ic0.canister_version : () -> i64;                                                  // *
+    ic0.cycles_burn128 : (amount_high : i64, amount_low : i64, dst : isize) -> ();     // I G U Ry Rt H
 
     ic0.msg_method_name_size : () -> isize;                                            // F
     ic0.msg_method_name_copy : (dst : isize, offset : isize, size : isize) -> ();      // F
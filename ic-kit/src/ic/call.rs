@@ -7,8 +7,7 @@ use candid::{decode_args, decode_one, encode_args, encode_one, CandidType, Princ
 use ic_kit_sys::ic0;
 use serde::de::DeserializeOwned;
 
-use ic_kit_sys::types::RejectionCode;
-pub use ic_kit_sys::types::{CallError, CANDID_EMPTY_ARG};
+pub use ic_kit_sys::types::{CallError, RejectionCode, CANDID_EMPTY_ARG};
 
 /// A call builder that let's you create an inter-canister call which can be then sent to the
 /// destination.
@@ -113,7 +112,7 @@ impl CallBuilder {
             ic0::call_cycles_add(self.payment as i64);
         } else if self.payment > 0 {
             let high = (self.payment >> 64) as u64 as i64;
-            let low = (self.payment & (1 << 64)) as u64 as i64;
+            let low = (self.payment & (u64::MAX as u128)) as u64 as i64;
             ic0::call_cycles_add128(high, low);
         }
 
@@ -45,6 +45,14 @@ pub fn id() -> Principal {
     with(CanisterPrincipalId::clone).0
 }
 
+/// The version of the canister. In the ic-kit runtime this is the version the canister was
+/// created with, which is only incremented after successful non-query messages if the canister
+/// is built with `with_auto_increment_version(true)`.
+#[inline(always)]
+pub fn canister_version() -> u64 {
+    unsafe { ic0::canister_version() as u64 }
+}
+
 /// The time in nanoseconds.
 #[inline(always)]
 pub fn time() -> u64 {
@@ -85,6 +93,10 @@ pub fn caller() -> Principal {
 /// Set the certified data of the canister, this method traps if data.len > 32.
 #[inline(always)]
 pub fn set_certified_data(data: &[u8]) {
+    debug_assert!(
+        data.len() <= 32,
+        "ic-kit: Certified data can not be more than 32 bytes, certify the root hash instead."
+    );
     unsafe { ic0::certified_data_set(data.as_ptr() as isize, data.len() as isize) }
 }
 
@@ -98,7 +110,7 @@ pub fn data_certificate() -> Option<Vec<u8>> {
     let n = unsafe { ic0::data_certificate_size() };
     let mut buf = vec![0u8; n as usize];
     unsafe {
-        ic0::data_certificate_copy(buf.as_mut_ptr() as isize, 9, n);
+        ic0::data_certificate_copy(buf.as_mut_ptr() as isize, 0, n);
     }
     Some(buf)
 }
@@ -36,11 +36,11 @@ pub fn msg_cycles_accept(max_amount: Cycles) -> Cycles {
     #[cfg(feature = "experimental-cycles128")]
     {
         if max_amount < (u64::MAX as u128) {
-            return unsafe { ic0::msg_cycles_accept(max_amount as i64) as u128 };
+            return unsafe { ic0::msg_cycles_accept(max_amount as i64) as u64 as u128 };
         }
 
         let high = (max_amount >> 64) as u64 as i64;
-        let low = (max_amount & (1 << 64)) as u64 as i64;
+        let low = (max_amount & (u64::MAX as u128)) as u64 as i64;
         let mut recv = 0u128;
         unsafe {
             ic0::msg_cycles_accept128(high, low, &mut recv as *mut u128 as isize);
@@ -65,3 +65,29 @@ pub fn msg_cycles_refunded() -> Cycles {
         u128::from_le(recv)
     }
 }
+
+/// Burn the given amount of cycles from the canister's balance, returns the actual amount of
+/// burned cycles which can be less than the requested amount if the balance is not enough.
+#[inline(always)]
+pub fn cycles_burn(amount: Cycles) -> Cycles {
+    #[cfg(not(feature = "experimental-cycles128"))]
+    {
+        let mut recv = 0u128;
+        unsafe {
+            ic0::cycles_burn128(0, amount as i64, &mut recv as *mut u128 as isize);
+        }
+        // The burned amount is never more than the requested amount, so it fits in a u64.
+        u128::from_le(recv) as u64
+    }
+
+    #[cfg(feature = "experimental-cycles128")]
+    {
+        let high = (amount >> 64) as u64 as i64;
+        let low = (amount & (u64::MAX as u128)) as u64 as i64;
+        let mut recv = 0u128;
+        unsafe {
+            ic0::cycles_burn128(high, low, &mut recv as *mut u128 as isize);
+        }
+        u128::from_le(recv)
+    }
+}
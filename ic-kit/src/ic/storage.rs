@@ -40,6 +40,12 @@ pub fn maybe_with_mut<T: 'static, U, F: FnOnce(&mut T) -> U>(callback: F) -> Opt
     STORAGE.with(|storage| storage.maybe_with_mut(callback))
 }
 
+/// Returns `true` if there is a value associated with the type `T`, this does not insert the
+/// default value like [`with`] does.
+pub fn contains<T: 'static>() -> bool {
+    STORAGE.with(|storage| storage.contains::<T>())
+}
+
 /// Remove the current value associated with the type and return it.
 pub fn take<T: 'static>() -> Option<T> {
     STORAGE.with(|storage| storage.take::<T>())
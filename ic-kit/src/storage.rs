@@ -1,5 +1,5 @@
 #![allow(non_snake_case)]
-use std::any::{Any, TypeId};
+use std::any::{type_name, Any, TypeId};
 use std::borrow::{Borrow, BorrowMut};
 use std::cell::RefCell;
 use std::collections::hash_map::Entry;
@@ -8,6 +8,15 @@ use std::ops::DerefMut;
 
 type StorageMap = HashMap<TypeId, RefCell<Box<dyn Any>>>;
 
+#[cold]
+#[inline(never)]
+fn invalid_type<T>() -> ! {
+    panic!(
+        "ic-kit: Unexpected value of invalid type in the storage, requested '{}'.",
+        type_name::<T>()
+    )
+}
+
 /// An storage implementation for singleton design pattern, where we only have one value
 /// associated with each types.
 #[derive(Default)]
@@ -37,7 +46,9 @@ impl Storage {
             .get(&tid)
             .unwrap()
             .borrow();
-        let borrow = cell.downcast_ref::<T>().unwrap();
+        let borrow = cell
+            .downcast_ref::<T>()
+            .unwrap_or_else(|| invalid_type::<T>());
         callback(borrow)
     }
 
@@ -50,7 +61,13 @@ impl Storage {
             .unwrap()
             .get(&tid)
             .map(|c| c.borrow())
-            .map(|c| callback(c.borrow().downcast_ref::<T>().unwrap()))
+            .map(|c| {
+                callback(
+                    c.borrow()
+                        .downcast_ref::<T>()
+                        .unwrap_or_else(|| invalid_type::<T>()),
+                )
+            })
     }
 
     /// Like [`Self::with`] but passes a mutable reference.
@@ -63,7 +80,9 @@ impl Storage {
             .get(&tid)
             .unwrap()
             .borrow_mut();
-        let borrow = cell.downcast_mut::<T>().unwrap();
+        let borrow = cell
+            .downcast_mut::<T>()
+            .unwrap_or_else(|| invalid_type::<T>());
         callback(borrow)
     }
 
@@ -75,17 +94,32 @@ impl Storage {
             .unwrap()
             .get(&tid)
             .map(|c| c.borrow_mut())
-            .map(|mut c| callback(c.borrow_mut().downcast_mut::<T>().unwrap()))
+            .map(|mut c| {
+                callback(
+                    c.borrow_mut()
+                        .downcast_mut::<T>()
+                        .unwrap_or_else(|| invalid_type::<T>()),
+                )
+            })
+    }
+
+    /// Returns `true` if there is a value associated with the type `T`, unlike `with` this
+    /// never inserts the default value.
+    #[inline]
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.storage.borrow().contains_key(&TypeId::of::<T>())
     }
 
     /// Remove the data associated with the type `T`, and returns it if any.
     #[inline]
     pub fn take<T: 'static>(&self) -> Option<T> {
         let tid = TypeId::of::<T>();
-        self.storage
-            .borrow_mut()
-            .remove(&tid)
-            .map(|cell| *cell.into_inner().downcast::<T>().unwrap())
+        self.storage.borrow_mut().remove(&tid).map(|cell| {
+            *cell
+                .into_inner()
+                .downcast::<T>()
+                .unwrap_or_else(|_| invalid_type::<T>())
+        })
     }
 
     /// Store the given value for type `T`, returns the previously stored value if any.
@@ -97,7 +131,7 @@ impl Storage {
                 *o.get_mut()
                     .replace(Box::new(value))
                     .downcast::<T>()
-                    .unwrap(),
+                    .unwrap_or_else(|_| invalid_type::<T>()),
             ),
             Entry::Vacant(v) => {
                 v.insert(RefCell::new(Box::new(value)));
@@ -167,7 +201,7 @@ macro_rules! implement_borrow_many {
                     $(
                     unsafe { &*(&*$name as *const Box<dyn Any>) }
                         .downcast_ref::<$name>()
-                        .unwrap(),
+                        .unwrap_or_else(|| invalid_type::<$name>()),
                     )+
                 ))
             }
@@ -196,7 +230,7 @@ macro_rules! implement_borrow_many {
                     $(
                     unsafe { &mut *(&mut *$name as *mut Box<dyn Any>) }
                         .downcast_mut::<$name>()
-                        .unwrap(),
+                        .unwrap_or_else(|| invalid_type::<$name>()),
                     )+
                 ))
             }
@@ -216,3 +250,20 @@ implement_borrow_many!(
     (A0 A1 A3 A4 A5 A6 A7 A8)
     (A0 A1 A3 A4 A5 A6 A7 A8 A9)
 );
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contains() {
+        let storage = Storage::default();
+        assert!(!storage.contains::<u64>());
+        assert_eq!(storage.maybe_with(|v: &u64| *v), None);
+        assert!(!storage.contains::<u64>());
+        storage.with(|_: &u64| ());
+        assert!(storage.contains::<u64>());
+        assert_eq!(storage.take::<u64>(), Some(0));
+        assert!(!storage.contains::<u64>());
+    }
+}
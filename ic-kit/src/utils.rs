@@ -58,6 +58,9 @@ pub fn method_name() -> String {
 ///
 /// 0 : Instruction counter.  
 ///     The number of WebAssembly instructions the system has determined that the canister has executed.
+///
+/// 1 : Call context instruction counter.  
+///     The number of WebAssembly instructions executed within the current call context.
 pub fn performance_counter(counter_type: u32) -> u64 {
     unsafe { ic0::performance_counter(counter_type as i32) as u64 }
 }